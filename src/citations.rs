@@ -0,0 +1,270 @@
+use crate::filings::Filing;
+use crate::news::NewsItem;
+use std::fmt::Write;
+use strsim::jaro_winkler;
+
+/// Row labels at least this similar to a cited label are taken as the cited row.
+const LABEL_SIMILARITY: f64 = 0.9;
+
+/// Statement codes the financial review cites rows with.
+const STATEMENT_CODES: &[(&str, &str)] = &[
+    ("IS", "Income Statement"),
+    ("BS", "Balance Sheet"),
+    ("CF", "Cash Flow"),
+];
+
+/// One rendered statement table, read back from the text given to the model.
+struct Statement {
+    code: &'static str,
+    title: String,
+    link: Option<String>,
+    periods: Vec<String>,
+    rows: Vec<(String, Vec<String>)>,
+}
+
+fn cells(line: &str) -> Vec<String> {
+    line.split('|')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Parse the tables produced by `display::print_scraped_table`, each followed by
+/// its `Source:` line.
+fn parse_statements(text: &str) -> Vec<Statement> {
+    let mut statements: Vec<Statement> = Vec::new();
+
+    for line in text.lines() {
+        if let Some((code, _)) = STATEMENT_CODES
+            .iter()
+            .find(|(_, name)| line.starts_with(name))
+        {
+            statements.push(Statement {
+                code,
+                title: line.trim().to_string(),
+                link: None,
+                periods: Vec::new(),
+                rows: Vec::new(),
+            });
+            continue;
+        }
+        let Some(statement) = statements.last_mut() else {
+            continue;
+        };
+        if let Some(link) = line.strip_prefix("Source: ") {
+            statement.link = Some(link.trim().to_string());
+        } else if line.contains('|') {
+            let mut row = cells(line);
+            if row.is_empty() {
+                continue;
+            }
+            let label = row.remove(0);
+            if label == "Metric" {
+                statement.periods = row;
+            } else {
+                statement.rows.push((label, row));
+            }
+        }
+    }
+    statements
+}
+
+/// Match a cited period such as "3/31/2024", "FY2024" or "TTM" to a column.
+fn find_period(periods: &[String], cited: &str) -> Option<usize> {
+    let cited = cited.trim();
+    if let Some(i) = periods.iter().position(|p| p.eq_ignore_ascii_case(cited)) {
+        return Some(i);
+    }
+    let year: String = cited.chars().filter(|c| c.is_ascii_digit()).collect();
+    let year = match year.len() {
+        4 => year,
+        2 => format!("20{year}"),
+        _ => return None,
+    };
+    periods.iter().position(|p| p.ends_with(&year))
+}
+
+fn find_row<'a>(
+    rows: &'a [(String, Vec<String>)],
+    cited: &str,
+) -> Option<&'a (String, Vec<String>)> {
+    let cited = cited.trim().to_lowercase();
+    rows.iter()
+        .find(|(label, _)| label.to_lowercase() == cited)
+        .or_else(|| {
+            rows.iter()
+                .map(|row| (jaro_winkler(&row.0.to_lowercase(), &cited), row))
+                .filter(|(score, _)| *score >= LABEL_SIMILARITY)
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, row)| row)
+        })
+}
+
+/// Describe one `CODE: Row, Period` citation as a footnote.
+fn describe_row(statements: &[Statement], citation: &str) -> Option<String> {
+    let (code, rest) = citation.split_once(':')?;
+    let code = code.trim().to_uppercase();
+    let (_, name) = STATEMENT_CODES.iter().find(|(c, _)| *c == code)?;
+    let (label, period) = rest.rsplit_once(',').unwrap_or((rest, ""));
+
+    // A `[MISSING]` placeholder parses as a statement without rows
+    let Some(statement) = statements
+        .iter()
+        .find(|s| s.code == code && !s.rows.is_empty())
+    else {
+        return Some(format!("{name}: {} — statement not available", rest.trim()));
+    };
+    let Some((row_label, values)) = find_row(&statement.rows, label) else {
+        return Some(format!(
+            "{}: {} — row not found in the statement",
+            statement.title,
+            label.trim()
+        ));
+    };
+
+    let mut out = format!("{}: {}", statement.title, row_label);
+    match find_period(&statement.periods, period) {
+        Some(col) => write!(
+            &mut out,
+            ", {} = {}",
+            statement.periods[col],
+            values.get(col).map_or("-", String::as_str)
+        )
+        .ok(),
+        None if !period.trim().is_empty() => write!(
+            &mut out,
+            ", {} — period not in the statement",
+            period.trim()
+        )
+        .ok(),
+        None => None,
+    };
+    if let Some(link) = &statement.link {
+        write!(&mut out, " ({link})").ok();
+    }
+    Some(out)
+}
+
+/// Replace every bracketed citation accepted by `describe` with numbered footnote
+/// markers and list the footnotes under "Sources:". Other brackets are left as is.
+fn footnote(text: &str, describe: impl Fn(&str) -> Option<Vec<String>>) -> String {
+    let mut out = String::new();
+    let mut notes: Vec<String> = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']').map(|i| open + i) else {
+            break;
+        };
+        // Not a citation: keep the bracket and look for one inside it
+        let Some(found) = describe(&rest[open + 1..close]).filter(|f| !f.is_empty()) else {
+            out.push_str(&rest[..=open]);
+            rest = &rest[open + 1..];
+            continue;
+        };
+        out.push_str(&rest[..open]);
+        for note in found {
+            let number = match notes.iter().position(|n| *n == note) {
+                Some(i) => i + 1,
+                None => {
+                    notes.push(note);
+                    notes.len()
+                }
+            };
+            write!(&mut out, "[{number}]").ok();
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+
+    if !notes.is_empty() {
+        writeln!(&mut out, "\n\nSources:").ok();
+        for (idx, note) in notes.iter().enumerate() {
+            writeln!(&mut out, "  [{}] {}", idx + 1, note).ok();
+        }
+    }
+    out
+}
+
+/// Turn `[IS: Total Revenue, 3/31/2024]` citations in the financial review into
+/// footnotes naming the statement row, its value and the page it was read from.
+pub fn cite_statements(review: &str, statements: &str) -> String {
+    let statements = parse_statements(statements);
+    footnote(review, |inner| {
+        let code = inner.split(':').next()?.trim().to_uppercase();
+        if !STATEMENT_CODES.iter().any(|(c, _)| *c == code) {
+            return None;
+        }
+        inner
+            .split(';')
+            .map(|citation| describe_row(&statements, citation))
+            .collect()
+    })
+}
+
+fn numbers(v: &str) -> Vec<usize> {
+    v.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Turn `[Article 2, 5]` and `[Filing 1]` citations in the news review into footnotes
+/// with each article's title, date and link, or each filing's attachment.
+pub fn cite_news(review: &str, items: &[NewsItem], filings: &[Filing]) -> String {
+    let material: Vec<&Filing> = filings.iter().filter(|f| f.material).collect();
+    footnote(review, |inner| {
+        let mut notes = Vec::new();
+        for part in inner.split(';') {
+            let part = part.trim();
+            let lower = part.to_lowercase();
+            if lower.starts_with("article") {
+                for n in numbers(part) {
+                    let Some(item) = n.checked_sub(1).and_then(|i| items.get(i)) else {
+                        notes.push(format!(
+                            "Article {} not found: only {} articles",
+                            n,
+                            items.len()
+                        ));
+                        continue;
+                    };
+                    notes.push(format!(
+                        "Article {}: {} — {}{} — {}",
+                        n,
+                        item.title,
+                        item.publisher
+                            .as_deref()
+                            .map(|p| format!("{p}, "))
+                            .unwrap_or_default(),
+                        item.published_at.format("%Y-%m-%d"),
+                        item.link
+                    ));
+                }
+            } else if lower.starts_with("filing") {
+                for n in numbers(part) {
+                    let Some(filing) = n.checked_sub(1).and_then(|i| material.get(i)) else {
+                        notes.push(format!(
+                            "Filing {} not found: only {} material filings",
+                            n,
+                            material.len()
+                        ));
+                        continue;
+                    };
+                    notes.push(format!(
+                        "Filing {}: {} {}, {} — {}",
+                        n,
+                        filing.exchange,
+                        filing.published_at.format("%Y-%m-%d"),
+                        filing.category,
+                        filing
+                            .attachment
+                            .as_ref()
+                            .map_or("no attachment", |a| a.url.as_str())
+                    ));
+                }
+            } else {
+                return None;
+            }
+        }
+        Some(notes)
+    })
+}
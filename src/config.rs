@@ -9,10 +9,76 @@ fn config_path() -> PathBuf {
         .unwrap_or_else(|_| PathBuf::from(".scout_config"))
 }
 
+/// How HTTP traffic interacts with the fixture directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Talk to the network, never touch fixtures.
+    Live,
+    /// Talk to the network and save every response as a fixture.
+    Record,
+    /// Serve responses from fixtures only, never touch the network.
+    Replay,
+}
+
+impl FixtureMode {
+    fn parse(v: &str) -> Self {
+        match v.to_lowercase().as_str() {
+            "record" => FixtureMode::Record,
+            "replay" => FixtureMode::Replay,
+            _ => FixtureMode::Live,
+        }
+    }
+}
+
+/// Which model scores per-article news sentiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SentimentModel {
+    Llm,
+    Lexicon,
+}
+
+/// A symbol master for an extra exchange, from `SCOUT_EXCHANGES`.
+#[derive(Debug, Clone)]
+pub struct ExchangeMaster {
+    pub code: String,
+    /// CSV file name, looked up in the data directories.
+    pub file: String,
+    /// Yahoo suffix; the known suffix for `code` when not given.
+    pub suffix: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub model: String,
     pub ollama_host: String,
+    pub fixture_mode: FixtureMode,
+    pub fixture_dir: PathBuf,
+    pub connect_timeout_secs: u64,
+    pub read_timeout_secs: u64,
+    pub llm_timeout_secs: u64,
+    pub max_retries: u32,
+    pub retry_backoff_ms: u64,
+    pub query_deadline_secs: u64,
+    pub news_concurrency: usize,
+    pub news_min_relevance: f64,
+    pub drop_irrelevant_news: bool,
+    pub sentiment_model: SentimentModel,
+    pub sentiment_half_life_days: f64,
+    /// Overrides the context length reported by the model.
+    pub context_tokens: Option<usize>,
+    /// Extra RSS/Atom feeds as (name, url) pairs.
+    pub news_feeds: Vec<(String, String)>,
+    /// Directory searched first for symbol files such as `nse.csv`.
+    pub data_dir: Option<PathBuf>,
+    /// Exchange whose listing is used for dual-listed companies, e.g. "NSE".
+    pub preferred_exchange: String,
+    pub exchanges: Vec<ExchangeMaster>,
+    /// NSE series matched by company name; empty matches every series.
+    pub series: Vec<String>,
+    /// Ask Yahoo for a quote when a ticker is not in the symbol masters.
+    pub quote_check: bool,
+    /// Print how each query's ticker was resolved.
+    pub verbose: bool,
 }
 
 impl Default for Config {
@@ -20,11 +86,137 @@ impl Default for Config {
         Self {
             model: "qwen2.5:7b-instruct".to_string(),
             ollama_host: "http://127.0.0.1:11434/api/generate".to_string(),
+            fixture_mode: FixtureMode::Live,
+            fixture_dir: PathBuf::from("fixtures"),
+            connect_timeout_secs: 10,
+            read_timeout_secs: 30,
+            llm_timeout_secs: 300,
+            max_retries: 3,
+            retry_backoff_ms: 500,
+            query_deadline_secs: 900,
+            news_concurrency: 4,
+            news_min_relevance: 0.3,
+            drop_irrelevant_news: true,
+            sentiment_model: SentimentModel::Llm,
+            sentiment_half_life_days: 3.0,
+            context_tokens: None,
+            news_feeds: Vec::new(),
+            data_dir: None,
+            preferred_exchange: "NSE".to_string(),
+            exchanges: Vec::new(),
+            series: vec!["EQ".to_string()],
+            quote_check: false,
+            verbose: false,
         }
     }
 }
 
 impl Config {
+    /// Keys that may be set in the config file or overridden from the environment.
+    const KEYS: &'static [&'static str] = &[
+        "OLLAMA_MODEL",
+        "OLLAMA_API_BASE_URL",
+        "SCOUT_FIXTURE_MODE",
+        "SCOUT_FIXTURE_DIR",
+        "SCOUT_CONNECT_TIMEOUT_SECS",
+        "SCOUT_READ_TIMEOUT_SECS",
+        "SCOUT_LLM_TIMEOUT_SECS",
+        "SCOUT_MAX_RETRIES",
+        "SCOUT_RETRY_BACKOFF_MS",
+        "SCOUT_QUERY_DEADLINE_SECS",
+        "SCOUT_NEWS_CONCURRENCY",
+        "SCOUT_NEWS_MIN_RELEVANCE",
+        "SCOUT_NEWS_IRRELEVANT",
+        "SCOUT_SENTIMENT",
+        "SCOUT_SENTIMENT_HALF_LIFE_DAYS",
+        "SCOUT_CONTEXT_TOKENS",
+        "SCOUT_NEWS_FEEDS",
+        "SCOUT_DATA_DIR",
+        "SCOUT_PREFERRED_EXCHANGE",
+        "SCOUT_EXCHANGES",
+        "SCOUT_SERIES",
+        "SCOUT_QUOTE_CHECK",
+        "SCOUT_VERBOSE",
+    ];
+
+    fn apply(&mut self, k: &str, v: &str) {
+        let num = |v: &str, current: u64| v.parse().unwrap_or(current);
+        match k {
+            "OLLAMA_MODEL" => self.model = v.to_string(),
+            "OLLAMA_API_BASE_URL" => self.ollama_host = v.to_string(),
+            "SCOUT_FIXTURE_MODE" => self.fixture_mode = FixtureMode::parse(v),
+            "SCOUT_FIXTURE_DIR" => self.fixture_dir = PathBuf::from(v),
+            "SCOUT_CONNECT_TIMEOUT_SECS" => {
+                self.connect_timeout_secs = num(v, self.connect_timeout_secs)
+            }
+            "SCOUT_READ_TIMEOUT_SECS" => self.read_timeout_secs = num(v, self.read_timeout_secs),
+            "SCOUT_LLM_TIMEOUT_SECS" => self.llm_timeout_secs = num(v, self.llm_timeout_secs),
+            "SCOUT_MAX_RETRIES" => self.max_retries = v.parse().unwrap_or(self.max_retries),
+            "SCOUT_RETRY_BACKOFF_MS" => self.retry_backoff_ms = num(v, self.retry_backoff_ms),
+            "SCOUT_QUERY_DEADLINE_SECS" => {
+                self.query_deadline_secs = num(v, self.query_deadline_secs)
+            }
+            "SCOUT_NEWS_CONCURRENCY" => {
+                self.news_concurrency = v.parse().unwrap_or(self.news_concurrency)
+            }
+            "SCOUT_NEWS_MIN_RELEVANCE" => {
+                self.news_min_relevance = v.parse().unwrap_or(self.news_min_relevance)
+            }
+            "SCOUT_NEWS_IRRELEVANT" => self.drop_irrelevant_news = !v.eq_ignore_ascii_case("flag"),
+            "SCOUT_SENTIMENT" => {
+                self.sentiment_model = if v.eq_ignore_ascii_case("lexicon") {
+                    SentimentModel::Lexicon
+                } else {
+                    SentimentModel::Llm
+                }
+            }
+            "SCOUT_SENTIMENT_HALF_LIFE_DAYS" => {
+                self.sentiment_half_life_days = v.parse().unwrap_or(self.sentiment_half_life_days)
+            }
+            "SCOUT_CONTEXT_TOKENS" => self.context_tokens = v.parse().ok(),
+            "SCOUT_NEWS_FEEDS" => {
+                self.news_feeds = v
+                    .split(',')
+                    .filter_map(|feed| {
+                        let (name, url) = feed.split_once('|')?;
+                        Some((name.trim().to_string(), url.trim().to_string()))
+                    })
+                    .collect()
+            }
+            "SCOUT_DATA_DIR" => self.data_dir = Some(PathBuf::from(v)),
+            "SCOUT_PREFERRED_EXCHANGE" => self.preferred_exchange = v.to_uppercase(),
+            "SCOUT_QUOTE_CHECK" => {
+                self.quote_check = matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+            }
+            "SCOUT_VERBOSE" => {
+                self.verbose = matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+            }
+            "SCOUT_SERIES" => {
+                self.series = v
+                    .split(',')
+                    .map(|s| s.trim().to_uppercase())
+                    .filter(|s| !s.is_empty() && s != "*")
+                    .collect()
+            }
+            "SCOUT_EXCHANGES" => {
+                self.exchanges = v
+                    .split(',')
+                    .filter_map(|entry| {
+                        let mut parts = entry.split('|').map(str::trim);
+                        let code = parts.next().filter(|c| !c.is_empty())?;
+                        let file = parts.next().filter(|f| !f.is_empty())?;
+                        Some(ExchangeMaster {
+                            code: code.to_uppercase(),
+                            file: file.to_string(),
+                            suffix: parts.next().map(String::from),
+                        })
+                    })
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+
     pub fn from_env() -> Result<Self> {
         let mut config = Config::default();
 
@@ -35,22 +227,15 @@ impl Config {
                     continue;
                 }
                 if let Some((k, v)) = line.split_once('=') {
-                    let k = k.trim();
-                    let v = v.trim().trim_matches('"');
-                    match k {
-                        "OLLAMA_MODEL" => config.model = v.to_string(),
-                        "OLLAMA_API_BASE_URL" => config.ollama_host = v.to_string(),
-                        _ => {}
-                    }
+                    config.apply(k.trim(), v.trim().trim_matches('"'));
                 }
             }
         }
 
-        if let Ok(val) = env::var("OLLAMA_MODEL") {
-            config.model = val;
-        }
-        if let Ok(val) = env::var("OLLAMA_API_BASE_URL") {
-            config.ollama_host = val;
+        for key in Self::KEYS {
+            if let Ok(val) = env::var(key) {
+                config.apply(key, &val);
+            }
         }
 
         Ok(config)
@@ -0,0 +1,136 @@
+use crate::extract;
+use scraper::{Html, Selector};
+
+/// What a fetched news page actually contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageKind {
+    Article,
+    ConsentWall,
+    Paywall,
+}
+
+/// Hosts and query markers that Yahoo uses when redirecting to its GUCE consent flow.
+const CONSENT_URL_MARKERS: &[&str] = &[
+    "consent.yahoo.com",
+    "guce.yahoo.com",
+    "guccounter=",
+    "/collectconsent",
+];
+
+const CONSENT_TEXT_MARKERS: &[&str] = &[
+    "yahoo family of brands",
+    "click ' reject all '",
+    "click 'reject all'",
+    "manage privacy settings",
+    "privacy and cookie settings",
+    "privacy dashboard",
+    "use cookies and personal data",
+    "we and our partners",
+    "accept all cookies",
+    "your privacy choices",
+];
+
+const PAYWALL_TEXT_MARKERS: &[&str] = &[
+    "subscribe to continue reading",
+    "subscribe to read",
+    "this article is for subscribers",
+    "this story is for subscribers",
+    "already a subscriber",
+    "to continue reading, subscribe",
+    "sign in to read the full article",
+    "unlock this article",
+];
+
+/// Article bodies of at least this many words are checked for consent text on their
+/// own; shorter ones, such as the little a consent wall leaves, fall back to the
+/// visible page text.
+const CONSENT_MIN_BODY_WORDS: usize = 50;
+
+/// Article bodies shorter than this many words may be a paywall teaser; longer
+/// ones are readable whatever the page says about subscribing.
+const PAYWALL_MAX_WORDS: usize = 150;
+
+fn count_markers(text: &str, markers: &[&str]) -> usize {
+    let l = text.to_lowercase();
+    markers.iter().filter(|m| l.contains(*m)).count()
+}
+
+/// True when text reads like cookie/privacy consent boilerplate rather than news.
+/// Two distinct markers are required so articles that merely mention privacy pass.
+pub fn is_consent_text(text: &str) -> bool {
+    count_markers(text, CONSENT_TEXT_MARKERS) >= 2
+}
+
+pub fn classify(url: &str, html: &str) -> PageKind {
+    let l = url.to_lowercase();
+    if CONSENT_URL_MARKERS.iter().any(|m| l.contains(m)) {
+        return PageKind::ConsentWall;
+    }
+
+    // Footers link to "Your Privacy Choices" and navigation says "already a
+    // subscriber?", so the article body is checked rather than the whole page
+    let body = extract::extract_article(html).body;
+    let words = body.split_whitespace().count();
+    let text = extract::visible_text(html);
+
+    let consent_checked = if words < CONSENT_MIN_BODY_WORDS {
+        &text
+    } else {
+        &body
+    };
+    if is_consent_text(consent_checked) {
+        return PageKind::ConsentWall;
+    }
+
+    // A paywall only counts when the body is too short to be the full story
+    let checked = if body.is_empty() { &text } else { &body };
+    if words < PAYWALL_MAX_WORDS && count_markers(checked, PAYWALL_TEXT_MARKERS) > 0 {
+        PageKind::Paywall
+    } else {
+        PageKind::Article
+    }
+}
+
+/// Extract the consent form (absolute action URL and fields) from a consent page.
+/// The submitted choice is "reject", which only stores the minimal required cookies.
+pub fn consent_form(url: &str, html: &str) -> Option<(String, Vec<(String, String)>)> {
+    let document = Html::parse_document(html);
+    let form_sel = Selector::parse("form").ok()?;
+    let hidden_sel = Selector::parse(r#"input[type="hidden"]"#).ok()?;
+    let button_sel = Selector::parse("button[name], input[type=\"submit\"][name]").ok()?;
+
+    for form in document.select(&form_sel) {
+        let buttons: Vec<(String, String)> = form
+            .select(&button_sel)
+            .filter_map(|b| {
+                let name = b.value().attr("name")?.to_string();
+                let value = b.value().attr("value").unwrap_or_default().to_string();
+                Some((name, value))
+            })
+            .collect();
+
+        let choice = buttons
+            .iter()
+            .find(|(name, _)| name == "reject")
+            .or_else(|| buttons.iter().find(|(name, _)| name == "agree"));
+        let Some(choice) = choice else {
+            continue;
+        };
+
+        let mut fields: Vec<(String, String)> = form
+            .select(&hidden_sel)
+            .filter_map(|i| {
+                let name = i.value().attr("name")?.to_string();
+                let value = i.value().attr("value").unwrap_or_default().to_string();
+                Some((name, value))
+            })
+            .collect();
+        fields.push(choice.clone());
+
+        let action = form.value().attr("action").unwrap_or_default();
+        let action = reqwest::Url::parse(url).ok()?.join(action).ok()?;
+        return Some((action.to_string(), fields));
+    }
+
+    None
+}
@@ -1,3 +1,7 @@
+use crate::filings::Filing;
+use crate::news::NewsItem;
+use crate::sentiment;
+use crate::symbols::Listing;
 use std::fmt::Write;
 
 fn format_num(n: f64) -> String {
@@ -74,3 +78,147 @@ pub fn print_scraped_table(
 
     out
 }
+
+/// Placeholder written in place of a statement that could not be fetched, so both
+/// the reader and the LLM see explicitly that the section is missing.
+pub fn missing_section(title: &str, reason: &str) -> String {
+    format!("\n{title}\n[MISSING] {title} could not be fetched: {reason}\n")
+}
+
+/// Print one line per data source; `None` means the source was fetched successfully.
+pub fn print_source_status(sources: &[(&str, Option<String>)]) {
+    println!("\nSource status:");
+    for (name, err) in sources {
+        match err {
+            None => println!("  {:<18} OK", name),
+            Some(e) => println!("  {:<18} MISSING ({})", name, e),
+        }
+    }
+    println!();
+}
+
+/// The first `max` characters of `text`.
+pub fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+/// Print one row per article with its sentiment, then the time-weighted aggregate.
+pub fn print_sentiment_table(items: &[NewsItem], aggregate: Option<f64>) {
+    let title_width = 48usize;
+    let sep = "-".repeat(4 + 12 + 10 + 6 + title_width + 15);
+
+    println!("\n========== NEWS SENTIMENT BY ARTICLE ==========\n");
+    println!(
+        "{:>3} | {:<10} | {:<8} | {:>4} | {:<width$} | Link",
+        "#",
+        "Date",
+        "Sent.",
+        "Conf",
+        "Title",
+        width = title_width
+    );
+    println!("{}", sep);
+    for (idx, item) in items.iter().enumerate() {
+        let Some(s) = &item.sentiment else {
+            continue;
+        };
+        println!(
+            "{:>3} | {:<10} | {:<8} | {:>4.2} | {:<width$} | {}",
+            idx + 1,
+            item.published_at.format("%Y-%m-%d"),
+            s.label.as_str(),
+            s.confidence,
+            truncate_chars(&item.title, title_width),
+            item.link,
+            width = title_width
+        );
+        if !s.rationale.is_empty() {
+            println!("{:>3} | {}", "", s.rationale);
+        }
+    }
+    println!("{}", sep);
+    match aggregate {
+        Some(score) => println!(
+            "Time-weighted sentiment: {:+.2} ({})\n",
+            score,
+            sentiment::describe(score)
+        ),
+        None => println!("Time-weighted sentiment: n/a\n"),
+    }
+}
+
+/// Print the exchange filings, marking the material ones passed to the news review.
+pub fn print_filings_table(filings: &[Filing]) {
+    let subject_width = 60usize;
+    let sep = "-".repeat(4 + 12 + 4 + 28 + subject_width + 7 + 13 + 6);
+
+    println!("\n========== EXCHANGE FILINGS ==========\n");
+    if filings.is_empty() {
+        println!("No filings in this period.\n");
+        return;
+    }
+    println!(
+        "{:>3} | {:<10} | {:<1} | {:<25} | {:<width$} | {:<4} | {:<10} | XBRL",
+        "#",
+        "Date",
+        "M",
+        "Category",
+        "Subject",
+        "File",
+        "Size",
+        width = subject_width
+    );
+    println!("{}", sep);
+    for (idx, f) in filings.iter().enumerate() {
+        println!(
+            "{:>3} | {:<10} | {:<1} | {:<25} | {:<width$} | {:<4} | {:<10} | {}",
+            idx + 1,
+            f.published_at.format("%Y-%m-%d"),
+            if f.material { "*" } else { "" },
+            truncate_chars(&f.category, 25),
+            truncate_chars(&f.subject, subject_width),
+            f.attachment.as_ref().map_or("-", |a| a.kind.as_str()),
+            f.attachment
+                .as_ref()
+                .and_then(|a| a.size.as_deref())
+                .map_or("-", |size| truncate_chars(size, 10)),
+            if f.has_xbrl { "yes" } else { "-" },
+            width = subject_width
+        );
+    }
+    println!("{}", sep);
+    println!(
+        "{} material of {} filings (marked *)\n",
+        filings.iter().filter(|f| f.material).count(),
+        filings.len()
+    );
+}
+
+/// Print the symbol-master rows shown by `/info`.
+pub fn print_listing_info(query: &str, listings: &[Listing]) {
+    println!("\n========== SYMBOL INFO ==========\n");
+    if listings.is_empty() {
+        println!("No listing found for {}.\n", query.trim());
+        return;
+    }
+    let or_dash = |v: &str| {
+        if v.is_empty() {
+            "-".to_string()
+        } else {
+            v.to_string()
+        }
+    };
+    for listing in listings {
+        println!("  {:<14} {}", "Yahoo symbol", listing.yahoo_symbol());
+        println!("  {:<14} {}", "Company", listing.name);
+        println!("  {:<14} {}", "Exchange", listing.exchange);
+        println!("  {:<14} {}", "Series", or_dash(&listing.series));
+        println!("  {:<14} {}", "Listed on", or_dash(&listing.listing_date));
+        println!("  {:<14} {}", "ISIN", or_dash(&listing.isin));
+        println!("  {:<14} {}", "Face value", or_dash(&listing.face_value));
+        println!();
+    }
+}
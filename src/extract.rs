@@ -0,0 +1,306 @@
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{HashMap, HashSet};
+
+/// Main content of a news page plus whatever metadata the page exposes.
+#[derive(Debug, Clone, Default)]
+pub struct Article {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub published: Option<String>,
+    /// Symbols the page links to or tags, e.g. from `/quote/TATASTEEL.NS/` links.
+    pub tickers: Vec<String>,
+    pub body: String,
+}
+
+/// Elements whose text counts towards the score of their ancestors.
+const BLOCK_TAGS: &str = "p, pre, td, blockquote, li, h2, h3, h4";
+
+/// Subtrees that never hold article text.
+const SKIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form", "button", "svg",
+    "figure", "iframe",
+];
+
+/// Subtrees left out of the visible page text: code and site-wide furniture.
+const HIDDEN_TAGS: &[&str] = &["script", "style", "noscript", "template", "nav", "footer"];
+
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "body", "content", "entry", "main", "post", "story", "text", "caas",
+];
+
+const NEGATIVE_HINTS: &[&str] = &[
+    "comment",
+    "footer",
+    "sidebar",
+    "nav",
+    "menu",
+    "share",
+    "social",
+    "related",
+    "promo",
+    "advert",
+    "sponsor",
+    "consent",
+    "cookie",
+    "subscribe",
+    "newsletter",
+    "recommend",
+    "byline",
+    "caption",
+    "breadcrumb",
+    "widget",
+    "popup",
+    "modal",
+];
+
+/// Paragraphs that open trailing page furniture rather than article text.
+const TRAILING_MARKERS: &[&str] = &[
+    "read more",
+    "recommended stories",
+    "additional sources",
+    "go to accessibility shortcuts",
+];
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn element_text(el: ElementRef) -> String {
+    normalize(&el.text().collect::<Vec<_>>().join(" "))
+}
+
+/// Text of the whole page outside scripts, styles, navigation and footers.
+pub fn visible_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let text: Vec<&str> = document
+        .root_element()
+        .descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let hidden = node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| HIDDEN_TAGS.contains(&a.value().name()));
+            (!hidden).then_some(&**text)
+        })
+        .collect();
+    normalize(&text.join(" "))
+}
+
+fn hint_weight(el: ElementRef) -> f64 {
+    let attrs = format!(
+        "{} {}",
+        el.value().attr("class").unwrap_or_default(),
+        el.value().attr("id").unwrap_or_default()
+    )
+    .to_lowercase();
+    let mut weight = 0.0;
+    if POSITIVE_HINTS.iter().any(|h| attrs.contains(h)) {
+        weight += 25.0;
+    }
+    if NEGATIVE_HINTS.iter().any(|h| attrs.contains(h)) {
+        weight -= 25.0;
+    }
+    if el.value().name() == "article" || el.value().name() == "main" {
+        weight += 10.0;
+    }
+    weight
+}
+
+fn is_skipped(el: ElementRef) -> bool {
+    el.ancestors().filter_map(ElementRef::wrap).any(|a| {
+        SKIP_TAGS.contains(&a.value().name()) || {
+            let attrs = format!(
+                "{} {}",
+                a.value().attr("class").unwrap_or_default(),
+                a.value().attr("id").unwrap_or_default()
+            )
+            .to_lowercase();
+            ["consent", "cookie", "comment", "sidebar", "related"]
+                .iter()
+                .any(|h| attrs.contains(h))
+        }
+    })
+}
+
+/// Share of an element's text that sits inside links; menus and link lists score near 1.
+fn link_density(el: ElementRef, link_sel: &Selector) -> f64 {
+    let total = element_text(el).len();
+    if total == 0 {
+        return 1.0;
+    }
+    let linked: usize = el.select(link_sel).map(|a| element_text(a).len()).sum();
+    linked as f64 / total as f64
+}
+
+fn first_attr(document: &Html, selectors: &[(&str, &str)]) -> Option<String> {
+    for (selector, attr) in selectors {
+        let Ok(sel) = Selector::parse(selector) else {
+            continue;
+        };
+        for el in document.select(&sel) {
+            let value = if *attr == "text" {
+                element_text(el)
+            } else {
+                normalize(el.value().attr(attr).unwrap_or_default())
+            };
+            if !value.is_empty() {
+                return Some(value);
+            }
+        }
+    }
+    None
+}
+
+fn extract_metadata(document: &Html, article: &mut Article) {
+    article.title = first_attr(
+        document,
+        &[
+            (r#"meta[property="og:title"]"#, "content"),
+            ("h1", "text"),
+            ("title", "text"),
+        ],
+    );
+    article.author = first_attr(
+        document,
+        &[
+            (r#"meta[name="author"]"#, "content"),
+            (r#"meta[property="article:author"]"#, "content"),
+            (r#"[rel="author"]"#, "text"),
+            (r#"[itemprop="author"]"#, "text"),
+            (".byline-attr-author", "text"),
+            (".byline", "text"),
+        ],
+    );
+    article.published = first_attr(
+        document,
+        &[
+            (r#"meta[property="article:published_time"]"#, "content"),
+            (r#"meta[itemprop="datePublished"]"#, "content"),
+            (r#"meta[name="date"]"#, "content"),
+            ("time[datetime]", "datetime"),
+        ],
+    );
+}
+
+/// Collect ticker symbols from quote links and Yahoo's ticker chips, in page order.
+fn extract_tickers(document: &Html) -> Vec<String> {
+    let mut tickers: Vec<String> = Vec::new();
+    let mut push = |symbol: &str| {
+        let symbol = symbol.trim().to_uppercase();
+        if !symbol.is_empty() && !tickers.contains(&symbol) {
+            tickers.push(symbol);
+        }
+    };
+
+    if let Ok(sel) = Selector::parse(r#"a[href*="/quote/"]"#) {
+        for el in document.select(&sel) {
+            let href = el.value().attr("href").unwrap_or_default();
+            if let Some(rest) = href.split("/quote/").nth(1) {
+                let symbol = rest.split(['/', '?', '#']).next().unwrap_or_default();
+                push(&symbol.replace("%5E", "^"));
+            }
+        }
+    }
+    if let Ok(sel) = Selector::parse("fin-streamer[data-symbol], [data-ticker]") {
+        for el in document.select(&sel) {
+            let value = el.value();
+            push(
+                value
+                    .attr("data-symbol")
+                    .or(value.attr("data-ticker"))
+                    .unwrap_or_default(),
+            );
+        }
+    }
+    tickers
+}
+
+/// Score every ancestor of a text block by the length, comma count and link density
+/// of the text it holds, and return the element with the highest score.
+fn top_candidate<'a>(document: &'a Html, link_sel: &Selector) -> Option<ElementRef<'a>> {
+    let block_sel = Selector::parse(BLOCK_TAGS).ok()?;
+    let mut scores = HashMap::new();
+
+    for block in document.select(&block_sel) {
+        if is_skipped(block) {
+            continue;
+        }
+        let text = element_text(block);
+        if text.len() < 25 {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (text.len() as f64 / 100.0).min(3.0);
+
+        let ancestors = block.ancestors().filter_map(ElementRef::wrap).take(2);
+        for (depth, ancestor) in ancestors.enumerate() {
+            let share = if depth == 0 { score } else { score / 2.0 };
+            scores
+                .entry(ancestor.id())
+                .or_insert_with(|| (ancestor, hint_weight(ancestor)))
+                .1 += share;
+        }
+    }
+
+    scores
+        .into_values()
+        .map(|(el, score)| (el, score * (1.0 - link_density(el, link_sel))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(el, _)| el)
+}
+
+fn collect_body(candidate: ElementRef, link_sel: &Selector) -> String {
+    let Ok(block_sel) = Selector::parse("p, h2, h3, h4, li, blockquote, pre") else {
+        return String::new();
+    };
+    let Ok(nested_sel) = Selector::parse("p") else {
+        return String::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut paragraphs: Vec<String> = Vec::new();
+
+    for el in candidate.select(&block_sel) {
+        if is_skipped(el) {
+            continue;
+        }
+        // Containers holding their own <p> children are emitted through those children
+        if el.value().name() != "p" && el.select(&nested_sel).next().is_some() {
+            continue;
+        }
+        let text = element_text(el);
+        let is_heading = matches!(el.value().name(), "h2" | "h3" | "h4");
+        if text.len() < if is_heading { 15 } else { 40 } {
+            continue;
+        }
+        if link_density(el, link_sel) > 0.5 {
+            continue;
+        }
+        let lower = text.to_lowercase();
+        if TRAILING_MARKERS.iter().any(|m| lower.starts_with(m)) {
+            continue;
+        }
+        if !seen.insert(lower) {
+            continue;
+        }
+        paragraphs.push(text);
+    }
+
+    paragraphs.join("\n\n")
+}
+
+/// Extract the readable article from a news page, working on Yahoo and publisher pages alike.
+pub fn extract_article(html: &str) -> Article {
+    let document = Html::parse_document(html);
+    let mut article = Article::default();
+    extract_metadata(&document, &mut article);
+    article.tickers = extract_tickers(&document);
+
+    let Ok(link_sel) = Selector::parse("a") else {
+        return article;
+    };
+    if let Some(candidate) = top_candidate(&document, &link_sel) {
+        article.body = collect_body(candidate, &link_sel);
+    }
+    article
+}
@@ -0,0 +1,270 @@
+use crate::config::FixtureMode;
+use crate::http;
+use crate::untrusted;
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use std::fmt::Write;
+
+const NSE_HOME: &str = "https://www.nseindia.com/";
+const NSE_ANNOUNCEMENTS: &str =
+    "https://www.nseindia.com/api/corporate-announcements?index=equities&symbol=";
+
+/// Categories that move the stock or change what a shareholder owns. Anything else
+/// (trading window closures, newspaper copies, share certificate losses, ...) is routine.
+const MATERIAL_CATEGORIES: &[&str] = &[
+    "financial result",
+    "outcome of board meeting",
+    "board meeting",
+    "dividend",
+    "record date",
+    "book closure",
+    "bonus",
+    "split",
+    "buyback",
+    "buy back",
+    "pledge",
+    "encumbrance",
+    "acquisition",
+    "amalgamation",
+    "merger",
+    "scheme of arrangement",
+    "credit rating",
+    "change in director",
+    "change in management",
+    "resignation",
+    "appointment",
+    "allotment",
+    "rights issue",
+    "qualified institutions placement",
+    "preferential issue",
+    "insolvency",
+    "default",
+    "litigation",
+    "fraud",
+    "award of order",
+    "bagging",
+    "analysts/institutional investor meet",
+];
+
+/// A document attached to a filing, usually a PDF or an XBRL/ZIP bundle.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub url: String,
+    /// File extension in lower case, e.g. "pdf".
+    pub kind: String,
+    /// Size as reported by the exchange, e.g. "245 KB".
+    pub size: Option<String>,
+}
+
+/// One corporate announcement filed with the exchange.
+#[derive(Debug, Clone)]
+pub struct Filing {
+    pub exchange: &'static str,
+    pub category: String,
+    pub subject: String,
+    pub published_at: DateTime<Utc>,
+    pub attachment: Option<Attachment>,
+    pub has_xbrl: bool,
+    pub material: bool,
+}
+
+fn is_material(category: &str, subject: &str) -> bool {
+    let text = format!("{} {}", category, subject).to_lowercase();
+    MATERIAL_CATEGORIES.iter().any(|c| text.contains(c))
+}
+
+/// Exchange timestamps are Indian Standard Time without an offset.
+fn parse_ist(v: &str) -> Option<DateTime<Utc>> {
+    let ist = FixedOffset::east_opt(5 * 3600 + 30 * 60)?;
+    ["%Y-%m-%d %H:%M:%S", "%d-%b-%Y %H:%M:%S", "%Y%m%d%H%M%S"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(v.trim(), f).ok())
+        .and_then(|d| d.and_local_timezone(ist).single())
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn text(v: &serde_json::Value, key: &str) -> Option<String> {
+    v[key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && *s != "-")
+        .map(String::from)
+}
+
+fn attachment(url: Option<String>, size: Option<String>) -> Option<Attachment> {
+    let url = url?;
+    let kind = url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    Some(Attachment { url, kind, size })
+}
+
+/// Parse the JSON returned by NSE's corporate-announcements API (a bare array, or an
+/// object with a `data` array in older responses).
+pub fn parse_nse(json: &str) -> Result<Vec<Filing>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let rows = match &value {
+        serde_json::Value::Array(rows) => rows,
+        _ => value["data"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Unexpected NSE announcements response"))?,
+    };
+
+    let mut filings = Vec::new();
+    for row in rows {
+        let Some(published_at) = text(row, "sort_date")
+            .or_else(|| text(row, "an_dt"))
+            .and_then(|d| parse_ist(&d))
+        else {
+            continue;
+        };
+        let category = text(row, "desc").unwrap_or_else(|| "Announcement".to_string());
+        let subject = text(row, "attchmntText")
+            .map(|s| untrusted::sanitize(&s).0)
+            .unwrap_or_else(|| category.clone());
+        filings.push(Filing {
+            exchange: "NSE",
+            material: is_material(&category, &subject),
+            attachment: attachment(text(row, "attchmntFile"), text(row, "fileSize")),
+            has_xbrl: row["hasXbrl"].as_bool().unwrap_or(false),
+            category,
+            subject,
+            published_at,
+        });
+    }
+    Ok(filings)
+}
+
+/// Fetch the corporate announcements for an NSE-listed `symbol`, newest first,
+/// optionally limited to filings published from `cutoff` on. Symbols on other
+/// exchanges return no filings.
+pub async fn get_filings(
+    client: &http::Client,
+    symbol: &str,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<Vec<Filing>> {
+    let Some(base) = symbol.strip_suffix(".NS") else {
+        return Ok(Vec::new());
+    };
+
+    // NSE only answers API calls that carry the cookies set by its home page
+    if client.mode() != FixtureMode::Replay {
+        client.get(NSE_HOME).await.ok();
+    }
+    let url = format!("{}{}", NSE_ANNOUNCEMENTS, encode_symbol(base));
+    let response = client.get(&url).await?;
+    if !response.status.is_success() {
+        return Err(anyhow::anyhow!(
+            "NSE announcements request failed: {}",
+            response.status
+        ));
+    }
+
+    let mut filings = parse_nse(&response.body)?;
+    if let Some(cutoff) = cutoff {
+        filings.retain(|f| f.published_at >= cutoff);
+    }
+    filings.sort_by_key(|f| std::cmp::Reverse(f.published_at));
+    Ok(filings)
+}
+
+/// NSE symbols may contain `&` and `-` (e.g. M&M, BAJAJ-AUTO).
+fn encode_symbol(v: &str) -> String {
+    v.replace('&', "%26")
+}
+
+/// Render the material filings as their own block for the news review.
+pub fn render(filings: &[Filing]) -> String {
+    let mut out = String::new();
+    writeln!(&mut out, "\n========== Material Filings ==========").ok();
+    let material: Vec<&Filing> = filings.iter().filter(|f| f.material).collect();
+    if material.is_empty() {
+        writeln!(
+            &mut out,
+            "[NO FILINGS] No material exchange filings in this period.\n"
+        )
+        .ok();
+        return out;
+    }
+
+    let mut block = String::new();
+    for (idx, f) in material.iter().enumerate() {
+        writeln!(
+            &mut block,
+            "Filing {} ({}, {}): {}",
+            idx + 1,
+            f.exchange,
+            f.published_at.format("%Y-%m-%d"),
+            f.category
+        )
+        .ok();
+        if f.subject != f.category {
+            writeln!(&mut block, "Subject: {}", f.subject).ok();
+        }
+        if let Some(a) = &f.attachment {
+            let mut kind = a.kind.to_uppercase();
+            if let Some(size) = &a.size {
+                kind = format!("{kind}, {size}");
+            }
+            if f.has_xbrl {
+                kind.push_str(", XBRL available");
+            }
+            writeln!(&mut block, "Attachment: {} ({})", a.url, kind).ok();
+        }
+        writeln!(&mut block).ok();
+    }
+    out.push_str(&untrusted::wrap("Exchange filings", &block));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ANNOUNCEMENTS: &str = include_str!("../tests/fixtures/nse_announcements.json");
+
+    #[test]
+    fn parses_nse_announcements() {
+        let filings = parse_nse(ANNOUNCEMENTS).unwrap();
+        // The undated row is skipped
+        assert_eq!(filings.len(), 3);
+
+        let results = &filings[0];
+        assert_eq!(results.category, "Financial Result Updates");
+        assert!(results.material);
+        assert!(results.has_xbrl);
+        assert_eq!(
+            results.published_at,
+            Utc.with_ymd_and_hms(2025, 10, 17, 12, 45, 2).unwrap()
+        );
+        let attachment = results.attachment.as_ref().unwrap();
+        assert_eq!(attachment.kind, "pdf");
+        assert_eq!(attachment.size.as_deref(), Some("1.24 MB"));
+
+        let window = &filings[1];
+        assert!(!window.material);
+        assert_eq!(window.attachment.as_ref().unwrap().kind, "zip");
+        assert!(window.subject.contains(untrusted::REMOVED));
+        assert!(!window.subject.to_lowercase().contains("ignore previous"));
+
+        // Falls back to an_dt, the category as subject, and no attachment for "-"
+        let newspaper = &filings[2];
+        assert_eq!(newspaper.subject, newspaper.category);
+        assert!(newspaper.attachment.is_none());
+        assert_eq!(
+            newspaper.published_at,
+            Utc.with_ymd_and_hms(2025, 9, 19, 6, 30, 41).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_wrapped_data_array() {
+        let wrapped = format!("{{\"data\": {}}}", ANNOUNCEMENTS);
+        assert_eq!(parse_nse(&wrapped).unwrap().len(), 3);
+        assert!(parse_nse("{\"message\": \"error\"}").is_err());
+    }
+}
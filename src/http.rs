@@ -0,0 +1,278 @@
+use crate::config::{Config, FixtureMode};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Longest single backoff sleep, including server-provided `Retry-After` values.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A fully read HTTP response, either fresh from the network or loaded from a fixture.
+pub struct Response {
+    pub status: StatusCode,
+    /// URL the response was served from, after following redirects.
+    pub url: String,
+    pub body: String,
+}
+
+/// Wraps a `reqwest::Client` so every request shares the same timeout and retry
+/// policy, and can be recorded to or replayed from the fixture directory configured
+/// with `SCOUT_FIXTURE_MODE`/`SCOUT_FIXTURE_DIR`.
+#[derive(Clone)]
+pub struct Client {
+    inner: reqwest::Client,
+    mode: FixtureMode,
+    dir: PathBuf,
+    read_timeout: Duration,
+    llm_timeout: Duration,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+/// FNV-1a, used instead of `DefaultHasher` so fixture names stay stable across toolchains.
+fn fixture_key(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for b in part.bytes().chain(std::iter::once(0)) {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+impl Client {
+    /// Builds the underlying client with the configured connect timeout.
+    pub fn new(builder: reqwest::ClientBuilder) -> Result<Self> {
+        let config = Config::from_env()?;
+        let inner = builder
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+            .build()?;
+        Ok(Self {
+            inner,
+            mode: config.fixture_mode,
+            dir: config.fixture_dir,
+            read_timeout: Duration::from_secs(config.read_timeout_secs),
+            llm_timeout: Duration::from_secs(config.llm_timeout_secs),
+            max_retries: config.max_retries,
+            backoff: Duration::from_millis(config.retry_backoff_ms),
+        })
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    /// Timeout for non-model requests, for calls made outside this client.
+    pub fn read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    fn fixture_path(&self, kind: &str, parts: &[&str]) -> PathBuf {
+        self.dir
+            .join(format!("{}-{:016x}.json", kind, fixture_key(parts)))
+    }
+
+    fn load(&self, kind: &str, parts: &[&str]) -> Result<serde_json::Value> {
+        let path = self.fixture_path(kind, parts);
+        let content = fs::read_to_string(&path).map_err(|e| {
+            anyhow::anyhow!(
+                "No recorded fixture for {} {} ({}): {}",
+                kind,
+                parts.first().unwrap_or(&""),
+                path.display(),
+                e
+            )
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save(&self, kind: &str, parts: &[&str], value: &serde_json::Value) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.fixture_path(kind, parts);
+        fs::write(path, serde_json::to_string_pretty(value)?)?;
+        Ok(())
+    }
+
+    fn load_response(&self, kind: &str, parts: &[&str]) -> Result<Response> {
+        let fixture = self.load(kind, parts)?;
+        let status = fixture["status"]
+            .as_u64()
+            .and_then(|s| StatusCode::from_u16(s as u16).ok())
+            .ok_or_else(|| anyhow::anyhow!("Fixture has no valid status"))?;
+        let url = fixture["final_url"]
+            .as_str()
+            .or(fixture["url"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        let body = fixture["body"].as_str().unwrap_or_default().to_string();
+        Ok(Response { status, url, body })
+    }
+
+    fn save_response(&self, kind: &str, parts: &[&str], response: &Response) -> Result<()> {
+        let fixture = serde_json::json!({
+            "url": parts.first().unwrap_or(&""),
+            "final_url": response.url,
+            "status": response.status.as_u16(),
+            "body": response.body,
+        });
+        self.save(kind, parts, &fixture)
+    }
+
+    /// Sends the request built by `build`, retrying with exponential backoff on
+    /// 429, 5xx, connect errors and, when `retry_timeouts` is set, timeouts.
+    async fn send(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+        retry_timeouts: bool,
+    ) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let wait = self.backoff.saturating_mul(2u32.saturating_pow(attempt));
+            let result = match build().send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    let url = response.url().to_string();
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(Duration::from_secs);
+                    response
+                        .text()
+                        .await
+                        .map(|body| (Response { status, url, body }, retry_after))
+                }
+                Err(e) => Err(e),
+            };
+
+            let retryable = match &result {
+                Ok((response, _)) => {
+                    response.status == StatusCode::TOO_MANY_REQUESTS
+                        || response.status.is_server_error()
+                }
+                Err(e) => (retry_timeouts && e.is_timeout()) || e.is_connect(),
+            };
+
+            if !retryable || attempt >= self.max_retries {
+                return Ok(result?.0);
+            }
+
+            let wait = match &result {
+                Ok((_, Some(retry_after))) => (*retry_after).max(wait),
+                _ => wait,
+            };
+            attempt += 1;
+            tokio::time::sleep(wait.min(MAX_BACKOFF)).await;
+        }
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Response> {
+        if self.mode == FixtureMode::Replay {
+            return self.load_response("get", &[url]);
+        }
+
+        let response = self
+            .send(|| self.inner.get(url).timeout(self.read_timeout), true)
+            .await?;
+
+        if self.mode == FixtureMode::Record {
+            self.save_response("get", &[url], &response)?;
+        }
+        Ok(response)
+    }
+
+    pub async fn post_json(&self, url: &str, body: &serde_json::Value) -> Result<Response> {
+        let request = body.to_string();
+        if self.mode == FixtureMode::Replay {
+            return self.load_response("post", &[url, &request]);
+        }
+
+        // A model that timed out once would time out again; retrying it only burns
+        // the query deadline
+        let response = self
+            .send(
+                || self.inner.post(url).json(body).timeout(self.llm_timeout),
+                false,
+            )
+            .await?;
+
+        if self.mode == FixtureMode::Record {
+            self.save_response("post", &[url, &request], &response)?;
+        }
+        Ok(response)
+    }
+
+    /// Submits an HTML form, used to answer consent pages before fetching an article.
+    pub async fn post_form(&self, url: &str, fields: &[(String, String)]) -> Result<Response> {
+        let request = fields
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        if self.mode == FixtureMode::Replay {
+            return self.load_response("form", &[url, &request]);
+        }
+
+        let response = self
+            .send(
+                || self.inner.post(url).form(fields).timeout(self.read_timeout),
+                true,
+            )
+            .await?;
+
+        if self.mode == FixtureMode::Record {
+            self.save_response("form", &[url, &request], &response)?;
+        }
+        Ok(response)
+    }
+
+    /// The time the query keyed by `key` runs at. Recording saves it with the
+    /// fixtures and replay reads it back, so date windows and age weights come out
+    /// the same however long after the recording a query is replayed.
+    pub fn query_time(&self, key: &str) -> Result<DateTime<Utc>> {
+        if self.mode != FixtureMode::Replay {
+            let now = Utc::now();
+            self.record_value("clock", key, serde_json::json!(now.to_rfc3339()))?;
+            return Ok(now);
+        }
+        match self.replay_value("clock", key) {
+            Ok(value) => value
+                .as_str()
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(|| anyhow::anyhow!("Fixture has no valid query time")),
+            // Recorded before query times were saved
+            Err(_) => {
+                println!(
+                    "- No recorded query time for {}; using the current time",
+                    key
+                );
+                Ok(Utc::now())
+            }
+        }
+    }
+
+    /// Loads a value that was produced outside this client (e.g. by `yfinance_rs`)
+    /// from its fixture. Only meaningful in replay mode.
+    pub fn replay_value(&self, kind: &str, key: &str) -> Result<serde_json::Value> {
+        let mut fixture = self.load(kind, &[key])?;
+        Ok(fixture["value"].take())
+    }
+
+    /// Saves a value that was produced outside this client when recording.
+    pub fn record_value(&self, kind: &str, key: &str, value: serde_json::Value) -> Result<()> {
+        if self.mode != FixtureMode::Record {
+            return Ok(());
+        }
+        self.save(
+            kind,
+            &[key],
+            &serde_json::json!({ "key": key, "value": value }),
+        )
+    }
+}
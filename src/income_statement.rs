@@ -1,5 +1,8 @@
+/// Row label followed by one cell per period column.
+pub type Row = (String, Vec<String>);
+
 /// Scrape the income statement table from the qsp-financials section.
-pub fn scrape_financials_table(html: &str) -> Option<(Vec<String>, Vec<(String, Vec<String>)>)> {
+pub fn scrape_financials_table(html: &str) -> Option<(Vec<String>, Vec<Row>)> {
     let doc = scraper::Html::parse_document(html);
     let section_sel = scraper::Selector::parse(r#"[data-testid="qsp-financials"]"#).ok()?;
     let section = doc.select(&section_sel).next()?;
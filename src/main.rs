@@ -1,17 +1,36 @@
 mod agent;
+mod citations;
 mod config;
+mod consent;
 mod display;
+mod extract;
+mod filings;
+mod http;
 mod income_statement;
 mod model_select;
+mod news;
+mod preparse;
+mod relevance;
+mod sentiment;
+mod sources;
+mod summarize;
+mod symbol_history;
+mod symbol_update;
+mod symbols;
+mod ticker_select;
 mod tools;
 mod ui;
+mod untrusted;
 mod user;
+mod verify;
 
 use anyhow::Result;
+use config::Config;
 use crossterm::{
     event::{Event, KeyCode, KeyEventKind, read},
     terminal::{disable_raw_mode, enable_raw_mode},
 };
+use std::time::Duration;
 
 fn reset_prompt(input: &mut String, prev_lines: &mut u16) {
     input.clear();
@@ -36,8 +55,142 @@ fn handle_error<E: std::fmt::Display>(e: E, input: &mut String, prev_lines: &mut
     reset_prompt(input, prev_lines);
 }
 
+/// Resolve the ticker, fetch every source concurrently and print both reviews.
+async fn run_query(input: &str, client: &http::Client, news_client: &http::Client) -> Result<()> {
+    let ticker = agent::get_ticker(input, client).await?;
+
+    println!("Resolved ticker: {}", ticker);
+    let window_days = tools::parse_news_window(input);
+    if let Some(days) = window_days {
+        println!("News window: last {} days", days);
+    }
+    let now = news_client.query_time(&ticker)?;
+    let cutoff = window_days.and_then(|days| tools::window_cutoff(now, days));
+    let mut output = String::new();
+    let (inc_res, bal_res, cash_res, news_res, filings_res) = tokio::join!(
+        tools::get_financials(&ticker, client, "income_statement"),
+        tools::get_financials(&ticker, client, "balance_sheet"),
+        tools::get_financials(&ticker, client, "cash_flow"),
+        tools::get_news(news_client, &ticker, cutoff),
+        filings::get_filings(news_client, &ticker, cutoff)
+    );
+
+    let mut statuses: Vec<(&str, Option<String>)> = Vec::new();
+    let mut statements_found = false;
+
+    for (name, res) in [
+        ("Income Statement", inc_res),
+        ("Balance Sheet", bal_res),
+        ("Cash Flow", cash_res),
+    ] {
+        match res {
+            Ok(val) => {
+                statements_found = true;
+                output.push_str(val.as_str());
+                statuses.push((name, None));
+            }
+            Err(e) => {
+                output.push_str(&display::missing_section(name, &e.to_string()));
+                statuses.push((name, Some(e.to_string())));
+            }
+        }
+    }
+
+    let news = news_res.map_err(|e| e.to_string());
+    statuses.push(("News", news.as_ref().err().cloned()));
+
+    // Only NSE symbols have an announcements feed
+    let filings = if ticker.ends_with(".NS") {
+        match filings_res {
+            Ok(val) => {
+                statuses.push(("Exchange Filings", None));
+                Some(val)
+            }
+            Err(e) => {
+                statuses.push(("Exchange Filings", Some(e.to_string())));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    display::print_source_status(&statuses);
+
+    if statements_found {
+        agent::get_financial_review(&output).await?;
+    } else {
+        println!("\n[Skipping financial analysis: no statements could be fetched]\n");
+    }
+    println!("\n\n");
+    if let Some(filings) = &filings {
+        display::print_filings_table(filings);
+    }
+    match news {
+        Ok(mut news) => {
+            sentiment::score_news(&mut news).await?;
+            let half_life = Config::from_env()?.sentiment_half_life_days;
+            let aggregate = sentiment::aggregate(&news, half_life, now);
+            display::print_sentiment_table(&news, aggregate);
+
+            let mut footer = match aggregate {
+                Some(score) => format!(
+                    "Aggregate time-weighted sentiment: {:+.2} ({})\n",
+                    score,
+                    sentiment::describe(score)
+                ),
+                None => String::new(),
+            };
+            if let Some(filings) = &filings {
+                footer.push_str(&filings::render(filings));
+            }
+            let context_tokens = agent::get_context_length().await?;
+            let news_text = summarize::prepare_news(&news, &footer, context_tokens).await?;
+            agent::get_news_review(
+                &news_text,
+                window_days,
+                context_tokens,
+                &news,
+                filings.as_deref().unwrap_or_default(),
+            )
+            .await?;
+        }
+        Err(error) => match &filings {
+            Some(filings) if filings.iter().any(|f| f.material) => {
+                println!("[News could not be fetched; reviewing exchange filings only]\n");
+                // Missing news is marked as such, not as a search that found nothing
+                let news_text =
+                    display::missing_section("News", &error) + &filings::render(filings);
+                let context_tokens = agent::get_context_length().await?;
+                agent::get_news_review(&news_text, window_days, context_tokens, &[], filings)
+                    .await?;
+            }
+            _ => println!("[Skipping news analysis: news could not be fetched]\n"),
+        },
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => {}
+        [command, sub, rest @ ..] if command == "symbols" && sub == "update" => {
+            symbol_update::run(rest).await?;
+            return Ok(());
+        }
+        [command, sub, rest @ ..] if command == "symbols" && sub == "changes" => {
+            symbol_history::import(rest).await?;
+            return Ok(());
+        }
+        _ => {
+            println!("Usage: scout [symbols update [PATH | URL] [--dry-run] [--force]]");
+            println!("       scout symbols changes [PATH | URL] [--dry-run]");
+            return Ok(());
+        }
+    }
+
     let client = user::user_client()?;
     let news_client = user::user_client()?;
 
@@ -67,66 +220,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         reset_with_banner(&mut input, &mut prev_lines);
                         continue;
                     }
+                    if let Some(rest) = input.trim().strip_prefix("/info")
+                        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+                    {
+                        disable_raw_mode()?;
+                        println!();
+                        match rest.trim() {
+                            "" => println!("Usage: /info <ticker or ISIN>\n"),
+                            query => display::print_listing_info(query, &tools::symbol_info(query)),
+                        }
+                        enable_raw_mode()?;
+                        reset_prompt(&mut input, &mut prev_lines);
+                        continue;
+                    }
                     disable_raw_mode()?;
                     println!();
 
-                    let ticker = match agent::get_ticker(&input).await {
-                        Ok(value) => value,
-                        Err(e) => {
-                            handle_error(e, &mut input, &mut prev_lines);
-                            continue;
-                        }
-                    };
-
-                    println!("Resolved ticker: {}", ticker);
-                    let mut output = String::new();
-                    let (inc_res, bal_res, cash_res, news_res) = tokio::join!(
-                        tools::get_financials(&ticker, &client, "income_statement"),
-                        tools::get_financials(&ticker, &client, "balance_sheet"),
-                        tools::get_financials(&ticker, &client, "cash_flow"),
-                        tools::get_news(&news_client, &ticker)
-                    );
-
-                    let news = match news_res {
-                        Ok(val) => val,
-                        Err(e) => {
-                            handle_error(e, &mut input, &mut prev_lines);
-                            continue;
-                        }
-                    };
-
-                    match inc_res {
-                        Ok(val) => output.push_str(val.as_str()),
-                        Err(e) => {
+                    let deadline = Duration::from_secs(Config::from_env()?.query_deadline_secs);
+                    match tokio::time::timeout(deadline, run_query(&input, &client, &news_client))
+                        .await
+                    {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => {
                             handle_error(e, &mut input, &mut prev_lines);
                             continue;
                         }
-                    };
-
-                    match bal_res {
-                        Ok(val) => output.push_str(val.as_str()),
-                        Err(e) => {
-                            handle_error(e, &mut input, &mut prev_lines);
+                        Err(_) => {
+                            handle_error(
+                                format!("Query exceeded the {}s deadline", deadline.as_secs()),
+                                &mut input,
+                                &mut prev_lines,
+                            );
                             continue;
                         }
-                    };
-
-                    match cash_res {
-                        Ok(val) => output.push_str(val.as_str()),
-                        Err(e) => {
-                            handle_error(e, &mut input, &mut prev_lines);
-                            continue;
-                        }
-                    };
-
-                    if let Err(e) = agent::get_financial_review(&output).await {
-                        handle_error(e, &mut input, &mut prev_lines);
-                        continue;
-                    }
-                    println!("\n\n");
-                    if let Err(e) = agent::get_news_review(&news).await {
-                        handle_error(e, &mut input, &mut prev_lines);
-                        continue;
                     }
                     if let Err(e) = enable_raw_mode() {
                         eprintln!("{e}");
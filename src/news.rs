@@ -0,0 +1,137 @@
+use crate::sentiment::Sentiment;
+use crate::untrusted;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+use yfinance_rs::news::NewsArticle;
+
+/// One news article after scraping, carrying what the later review stages need.
+#[derive(Debug, Clone)]
+pub struct NewsItem {
+    pub title: String,
+    pub link: String,
+    pub publisher: Option<String>,
+    pub published_at: DateTime<Utc>,
+    /// The listing gave no publish date; `published_at` is only meaningful once the
+    /// article page has supplied one.
+    pub undated: bool,
+    pub author: Option<String>,
+    pub tickers: Vec<String>,
+    pub body: String,
+    /// Short description supplied by the listing, if any.
+    pub summary: String,
+    /// Why the body is missing, e.g. "[UNAVAILABLE] Article is behind a paywall".
+    pub note: Option<String>,
+    /// How strongly the article is about the resolved company, from 0.0 to 1.0.
+    pub relevance: f64,
+    pub low_relevance: bool,
+    /// Text aimed at the model was removed from the title or body.
+    pub suspicious: bool,
+    pub sentiment: Option<Sentiment>,
+}
+
+impl NewsItem {
+    pub fn new(
+        title: String,
+        link: String,
+        publisher: Option<String>,
+        published_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            title,
+            link,
+            publisher,
+            published_at,
+            undated: false,
+            author: None,
+            tickers: Vec::new(),
+            body: String::new(),
+            summary: String::new(),
+            note: None,
+            relevance: 0.0,
+            low_relevance: false,
+            suspicious: false,
+            sentiment: None,
+        }
+    }
+
+    pub fn from_feed(article: NewsArticle) -> Self {
+        Self::new(
+            article.title,
+            article.link.unwrap_or_default(),
+            article.publisher,
+            article.published_at,
+        )
+    }
+}
+
+/// Render the metadata lines of article number `number` (1-based).
+pub fn render_header(number: usize, item: &NewsItem) -> String {
+    let mut out = String::new();
+    writeln!(&mut out, "========== Article {} ==========", number).ok();
+    writeln!(&mut out, "Title: {}", item.title).ok();
+    if let Some(publisher) = &item.publisher {
+        writeln!(&mut out, "Publisher: {}", publisher).ok();
+    }
+    writeln!(
+        &mut out,
+        "Published: {}",
+        item.published_at.format("%Y-%m-%d %H:%M UTC")
+    )
+    .ok();
+    writeln!(&mut out, "Link:  {}", item.link).ok();
+    if let Some(author) = &item.author {
+        writeln!(&mut out, "Author: {}", author).ok();
+    }
+    if !item.tickers.is_empty() {
+        writeln!(&mut out, "Tickers: {}", item.tickers.join(", ")).ok();
+    }
+    if let Some(sentiment) = &item.sentiment {
+        writeln!(
+            &mut out,
+            "Sentiment: {} ({:.2}) - {}",
+            sentiment.label.as_str(),
+            sentiment.confidence,
+            sentiment.rationale
+        )
+        .ok();
+    }
+    if item.low_relevance {
+        writeln!(
+            &mut out,
+            "[LOW RELEVANCE] Relevance {:.2}: this article may be about another company",
+            item.relevance
+        )
+        .ok();
+    }
+    if item.suspicious {
+        writeln!(
+            &mut out,
+            "[SUSPICIOUS] Instructions aimed at the model were removed from this article"
+        )
+        .ok();
+    }
+    writeln!(&mut out).ok();
+    out
+}
+
+/// The article text, or the note explaining why it is missing.
+pub fn body_text(item: &NewsItem) -> &str {
+    item.note.as_deref().unwrap_or(&item.body)
+}
+
+/// Render the numbered article blocks that are shown to the LLM.
+pub fn render(items: &[NewsItem]) -> String {
+    let mut out = String::new();
+    if items.is_empty() {
+        writeln!(&mut out, "[NO NEWS] No relevant articles were found.").ok();
+        return out;
+    }
+
+    for (idx, item) in items.iter().enumerate() {
+        let mut article = render_header(idx + 1, item);
+        article.push_str(body_text(item));
+        out.push_str(&untrusted::wrap(&format!("Article {}", idx + 1), &article));
+        writeln!(&mut out).ok();
+    }
+    out
+}
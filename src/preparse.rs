@@ -0,0 +1,149 @@
+use crate::symbols::{self, Candidate};
+use crate::ticker_select;
+use crate::tools;
+
+/// Name score needed to take a company name without asking the model.
+const HIGH_CONFIDENCE: f64 = 0.95;
+/// Longer inputs are treated as free-form questions, never as a bare company name.
+const MAX_NAME_WORDS: usize = 6;
+
+/// How a query's ticker was found, reported when `SCOUT_VERBOSE` is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Isin,
+    Symbol,
+    Renamed,
+    Alias,
+    Name,
+    /// The model extracted a company name, matched against the symbol masters.
+    LlmCompany,
+    /// The model extracted a ticker as written.
+    LlmTicker,
+}
+
+impl Resolution {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Isin => "ISIN in the query (no LLM call)",
+            Self::Symbol => "exact symbol (no LLM call)",
+            Self::Renamed => "old symbol in the symbol-change table (no LLM call)",
+            Self::Alias => "alias table (no LLM call)",
+            Self::Name => "high-confidence company name (no LLM call)",
+            Self::LlmCompany => "company name extracted by the LLM",
+            Self::LlmTicker => "ticker extracted by the LLM",
+        }
+    }
+}
+
+/// Upper-case words that look like a written symbol, e.g. "INFY" or "TCS.NS".
+/// Exchange codes are skipped since "BSE" is also a listed symbol.
+fn symbol_tokens(input: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = input
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '&' | '-')))
+        .map(|t| t.trim_end_matches(['.', '-']))
+        .filter(|t| {
+            t.len() >= 2
+                && t.chars().any(|c| c.is_ascii_uppercase())
+                && !t.chars().any(|c| c.is_ascii_lowercase())
+                && symbols::suffix_for(t).is_none()
+        })
+        .collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+/// Exchange codes named in the query, e.g. "BSE" in "TCS on BSE". A lower-case
+/// "us" is an ordinary word, not the US market.
+fn named_exchanges(input: &str) -> Vec<&'static str> {
+    let mut named: Vec<&'static str> = input
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|t| {
+            symbols::KNOWN_EXCHANGES
+                .iter()
+                .find(|(code, _)| t.eq_ignore_ascii_case(code) && (t == *code || code.len() > 2))
+                .map(|(code, _)| *code)
+        })
+        .collect();
+    named.sort_unstable();
+    named.dedup();
+    named
+}
+
+/// The same security's listing on `exchange`, when it is listed there.
+fn listed_on(candidate: Candidate, exchange: Option<&str>) -> Candidate {
+    let index = symbols::index();
+    match exchange.zip(index.by_symbol(&candidate.symbol)) {
+        Some((exchange, listing)) => {
+            let listing = index.prefer(listing, exchange);
+            Candidate {
+                symbol: listing.yahoo_symbol(),
+                name: listing.name.clone(),
+                ..candidate
+            }
+        }
+        None => candidate,
+    }
+}
+
+/// Resolve the query without the model when it is an ISIN, an exact symbol, an
+/// alias or a company name matched with high confidence, or a question naming
+/// exactly one symbol in capitals. An exchange named in a question is applied;
+/// a question naming several is left to the LLM parser, like anything else.
+pub fn preparse(input: &str) -> Option<(Candidate, Resolution)> {
+    let whole = input.trim().trim_end_matches(['?', '!', '.']).trim();
+    // A query of just "BSE" is the symbol, not the exchange
+    let single = !whole.contains(char::is_whitespace);
+    let exchanges = if single {
+        Vec::new()
+    } else {
+        named_exchanges(whole)
+    };
+    let exchange = match exchanges.as_slice() {
+        [only] => Some(*only),
+        _ => None,
+    };
+
+    if let Some(candidate) = tools::isin_in(input) {
+        return Some((listed_on(candidate, exchange), Resolution::Isin));
+    }
+    if whole.is_empty() || exchanges.len() > 1 {
+        return None;
+    }
+    if single {
+        if let Some(candidate) = tools::find_symbol(whole) {
+            return Some((candidate, Resolution::Symbol));
+        }
+        // Symbol changes are NSE-only, so "TATAMOTORS.NS" is renamed but not ".BO"
+        let (base, suffix) = tools::split_suffix(whole);
+        if (suffix.is_empty() || suffix.eq_ignore_ascii_case(".NS"))
+            && let Some(candidate) = tools::find_renamed(base)
+        {
+            return Some((candidate, Resolution::Renamed));
+        }
+    }
+    if let Some(candidate) = tools::find_alias(whole) {
+        return Some((listed_on(candidate, exchange), Resolution::Alias));
+    }
+    if whole.split_whitespace().count() <= MAX_NAME_WORDS {
+        let candidates = tools::find_ticker(whole, exchange);
+        if let Some(best) = candidates.first()
+            && best.score >= HIGH_CONFIDENCE
+            && !ticker_select::is_ambiguous(&candidates)
+        {
+            return Some((best.clone(), Resolution::Name));
+        }
+    }
+
+    let found: Vec<Candidate> = symbol_tokens(input)
+        .into_iter()
+        .filter_map(|token| match exchange {
+            Some(exchange) => tools::find_symbol(&tools::resolve_symbol(token, Some(exchange))),
+            None => tools::find_symbol(token),
+        })
+        .collect();
+    match found.as_slice() {
+        [only] => Some((only.clone(), Resolution::Symbol)),
+        _ => None,
+    }
+}
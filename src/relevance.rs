@@ -0,0 +1,86 @@
+use crate::symbols;
+use crate::tools;
+
+/// Words besides legal forms that appear in too many company names to identify one
+/// on their own.
+const GENERIC_WORDS: &[&str] = &["india", "indian", "industries", "the", "and", "of", "&"];
+
+/// The company a query resolved to, as used for scoring news relevance.
+pub struct Target {
+    symbol: String,
+    base: String,
+    name: Option<String>,
+    name_tokens: Vec<String>,
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric() && c != '&')
+        .any(|w| w == word)
+}
+
+impl Target {
+    pub fn new(symbol: &str) -> Self {
+        let base = symbol.split('.').next().unwrap_or(symbol).to_string();
+        let name = tools::find_company(symbol).map(|n| symbols::normalize(&n));
+        let name_tokens = name
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter(|w| !GENERIC_WORDS.contains(w) && !symbols::LEGAL_SUFFIXES.contains(w))
+            .map(String::from)
+            .collect();
+        Self {
+            symbol: symbol.to_uppercase(),
+            base: base.to_uppercase(),
+            name,
+            name_tokens,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.base)
+    }
+
+    /// Score from 0.0 to 1.0 combining ticker tags, symbol mentions and company name
+    /// mentions in the title and body.
+    pub fn score(&self, title: &str, body: &str, tickers: &[String]) -> f64 {
+        let mut score: f64 = 0.0;
+
+        if tickers
+            .iter()
+            .any(|t| t == &self.symbol || t.split('.').next() == Some(self.base.as_str()))
+        {
+            score += 0.6;
+        }
+
+        // Symbols are matched case-sensitively so "IDEA" does not match "idea"
+        if self.base.len() >= 3
+            && (contains_word(title, &self.base) || contains_word(body, &self.base))
+        {
+            score += 0.3;
+        }
+
+        // Normalised like the name, so "Dr. Reddy's Ltd." matches "dr reddy s"
+        let title_l = symbols::normalize(title);
+        let body_l = symbols::normalize(body);
+        if let Some(name) = self.name.as_deref().filter(|n| !n.is_empty()) {
+            if title_l.contains(name) {
+                score += 0.5;
+            } else if body_l.contains(name) {
+                score += 0.3;
+                if body_l.matches(name).count() >= 3 {
+                    score += 0.1;
+                }
+            } else if !self.name_tokens.is_empty()
+                && self
+                    .name_tokens
+                    .iter()
+                    .all(|t| contains_word(&title_l, t) || contains_word(&body_l, t))
+            {
+                score += 0.2;
+            }
+        }
+
+        score.min(1.0)
+    }
+}
@@ -0,0 +1,217 @@
+use crate::agent;
+use crate::config::{Config, SentimentModel};
+use crate::news::NewsItem;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+impl Label {
+    pub fn parse(v: &str) -> Option<Self> {
+        match v.trim().to_lowercase().as_str() {
+            "positive" => Some(Label::Positive),
+            "neutral" => Some(Label::Neutral),
+            "negative" => Some(Label::Negative),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Label::Positive => "positive",
+            Label::Neutral => "neutral",
+            Label::Negative => "negative",
+        }
+    }
+
+    fn value(self) -> f64 {
+        match self {
+            Label::Positive => 1.0,
+            Label::Neutral => 0.0,
+            Label::Negative => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sentiment {
+    pub label: Label,
+    /// 0.0 to 1.0.
+    pub confidence: f64,
+    pub rationale: String,
+}
+
+const POSITIVE_WORDS: &[&str] = &[
+    "beat",
+    "beats",
+    "growth",
+    "grew",
+    "surge",
+    "surged",
+    "rise",
+    "rises",
+    "rose",
+    "gain",
+    "gains",
+    "jump",
+    "jumped",
+    "profit",
+    "profits",
+    "record",
+    "upgrade",
+    "upgraded",
+    "strong",
+    "stronger",
+    "expand",
+    "expansion",
+    "outperform",
+    "dividend",
+    "approval",
+    "approved",
+    "win",
+    "wins",
+    "won",
+    "order",
+    "orders",
+    "rally",
+    "improved",
+    "improve",
+    "recovery",
+    "bullish",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "loss",
+    "losses",
+    "decline",
+    "declined",
+    "fall",
+    "fell",
+    "drop",
+    "dropped",
+    "miss",
+    "missed",
+    "downgrade",
+    "downgraded",
+    "weak",
+    "weaker",
+    "probe",
+    "fraud",
+    "default",
+    "cut",
+    "cuts",
+    "layoff",
+    "layoffs",
+    "slump",
+    "plunge",
+    "plunged",
+    "warn",
+    "warns",
+    "warning",
+    "lawsuit",
+    "penalty",
+    "fine",
+    "bearish",
+    "crisis",
+    "closure",
+    "shutdown",
+    "pledge",
+];
+
+/// Score text by counting finance-specific positive and negative words.
+pub fn lexicon(text: &str) -> Sentiment {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let pos = words.iter().filter(|w| POSITIVE_WORDS.contains(w)).count();
+    let neg = words.iter().filter(|w| NEGATIVE_WORDS.contains(w)).count();
+    let total = pos + neg;
+
+    if total == 0 {
+        return Sentiment {
+            label: Label::Neutral,
+            confidence: 0.3,
+            rationale: "No sentiment-bearing terms found".to_string(),
+        };
+    }
+
+    let balance = (pos as f64 - neg as f64) / total as f64;
+    let label = if balance > 0.2 {
+        Label::Positive
+    } else if balance < -0.2 {
+        Label::Negative
+    } else {
+        Label::Neutral
+    };
+    // More evidence and a more one-sided balance both raise confidence
+    let confidence = (balance.abs().max(0.2) * total as f64 / (total as f64 + 3.0)).min(1.0);
+
+    Sentiment {
+        label,
+        confidence,
+        rationale: format!("Lexicon: {pos} positive vs {neg} negative terms"),
+    }
+}
+
+/// Attach a sentiment to every article, using the configured model. Articles the
+/// LLM cannot score fall back to the lexicon.
+pub async fn score_news(items: &mut [NewsItem]) -> anyhow::Result<()> {
+    let config = Config::from_env()?;
+    for item in items.iter_mut() {
+        let text = format!("{}\n{}", item.title, item.body);
+        let sentiment = match config.sentiment_model {
+            SentimentModel::Lexicon => lexicon(&text),
+            SentimentModel::Llm => match agent::get_article_sentiment(item).await {
+                Ok(sentiment) => sentiment,
+                Err(e) => {
+                    println!(
+                        "- Sentiment fallback to lexicon for \"{}\": {}",
+                        item.title, e
+                    );
+                    lexicon(&text)
+                }
+            },
+        };
+        item.sentiment = Some(sentiment);
+    }
+    Ok(())
+}
+
+/// Confidence-weighted mean of article sentiment in [-1, 1], where an article's weight
+/// halves every `half_life_days` of age at `now`.
+pub fn aggregate(items: &[NewsItem], half_life_days: f64, now: DateTime<Utc>) -> Option<f64> {
+    let mut weighted = 0.0;
+    let mut total = 0.0;
+
+    for item in items {
+        let Some(sentiment) = &item.sentiment else {
+            continue;
+        };
+        let age_days = (now - item.published_at).num_minutes().max(0) as f64 / 1440.0;
+        let weight = 0.5f64.powf(age_days / half_life_days.max(0.1));
+        weighted += weight * sentiment.confidence * sentiment.label.value();
+        total += weight * sentiment.confidence;
+    }
+
+    if total > 0.0 {
+        Some(weighted / total)
+    } else {
+        None
+    }
+}
+
+pub fn describe(score: f64) -> &'static str {
+    if score > 0.15 {
+        "positive"
+    } else if score < -0.15 {
+        "negative"
+    } else {
+        "neutral"
+    }
+}
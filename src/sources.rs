@@ -0,0 +1,271 @@
+use crate::config::{Config, FixtureMode};
+use crate::http;
+use crate::news::NewsItem;
+use crate::relevance::Target;
+use crate::symbols::token_set_similarity;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use futures::future::BoxFuture;
+use yfinance_rs::news::NewsArticle;
+
+/// Most items taken from a single feed after filtering.
+const MAX_FEED_ITEMS: usize = 10;
+/// Titles at least this similar by token-set similarity are treated as the same
+/// story. A title whose words all appear in the other scores 1.0; one changed word
+/// ("profit rises" / "profit falls") stays below it.
+const DUPLICATE_TITLE_SIMILARITY: f64 = 0.98;
+
+/// A place article listings come from. Sources only list articles; bodies are
+/// scraped afterwards for every source alike.
+pub trait NewsSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn list<'a>(
+        &'a self,
+        client: &'a http::Client,
+        symbol: &'a str,
+        target: &'a Target,
+    ) -> BoxFuture<'a, Result<Vec<NewsItem>>>;
+}
+
+/// The Yahoo Finance news feed for the symbol, through `yfinance_rs`.
+pub struct YahooNews;
+
+impl NewsSource for YahooNews {
+    fn name(&self) -> &str {
+        "Yahoo Finance"
+    }
+
+    fn list<'a>(
+        &'a self,
+        client: &'a http::Client,
+        symbol: &'a str,
+        _target: &'a Target,
+    ) -> BoxFuture<'a, Result<Vec<NewsItem>>> {
+        Box::pin(async move {
+            let news: Vec<NewsArticle> = if client.mode() == FixtureMode::Replay {
+                serde_json::from_value(client.replay_value("news", symbol)?)?
+            } else {
+                let yf_client = yfinance_rs::YfClient::default();
+                let ticker = yfinance_rs::Ticker::new(&yf_client, symbol);
+                // yfinance_rs has its own client, so bound it with the read timeout here
+                let news = tokio::time::timeout(client.read_timeout(), ticker.news())
+                    .await
+                    .map_err(|_| anyhow::anyhow!("Yahoo news request timed out"))??;
+                client.record_value("news", symbol, serde_json::to_value(&news)?)?;
+                news
+            };
+
+            Ok(news
+                .into_iter()
+                .filter(|article| article.link.is_some())
+                .map(NewsItem::from_feed)
+                .collect())
+        })
+    }
+}
+
+/// A user-configured RSS 2.0 or Atom feed. `{symbol}` and `{company}` in the URL are
+/// filled in per query; feeds without them are treated as general market feeds and
+/// filtered down to entries that mention the company.
+pub struct FeedSource {
+    pub name: String,
+    pub url: String,
+}
+
+fn encode_query(v: &str) -> String {
+    v.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b' ' => "+".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Feed and page dates: RFC 2822 or RFC 3339, or an ISO date and time without an
+/// offset (taken as UTC) as some pages write in `<time datetime>`.
+pub fn parse_date(v: &str) -> Option<DateTime<Utc>> {
+    let v = v.trim();
+    DateTime::parse_from_rfc2822(v)
+        .or_else(|_| DateTime::parse_from_rfc3339(v))
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(v, "%Y-%m-%dT%H:%M:%S")
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(v, "%Y-%m-%d").map(|d| d.and_time(NaiveTime::MIN))
+                })
+                .ok()
+                .map(|d| d.and_utc())
+        })
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, 'a>, names: &[&str]) -> Option<&'a str> {
+    node.children()
+        .find(|c| c.is_element() && names.contains(&c.tag_name().name()))
+        .and_then(|c| c.text())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Feed descriptions are often HTML; keep only their text.
+fn strip_html(v: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(v);
+    fragment
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse RSS `<item>` and Atom `<entry>` elements into news items. Entries without
+/// a title or date are kept for the article page to fill in.
+pub fn parse_feed(xml: &str, publisher: &str) -> Result<Vec<NewsItem>> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let mut items = Vec::new();
+
+    for node in doc.descendants().filter(|n| n.is_element()) {
+        let (link, date, summary) = match node.tag_name().name() {
+            "item" => (
+                child_text(node, &["link", "guid"]).map(String::from),
+                child_text(node, &["pubDate", "date"]),
+                child_text(node, &["description"]),
+            ),
+            "entry" => {
+                let link = node
+                    .children()
+                    .filter(|c| c.tag_name().name() == "link")
+                    .find(|c| c.attribute("rel").is_none_or(|r| r == "alternate"))
+                    .and_then(|c| c.attribute("href"))
+                    .map(String::from);
+                (
+                    link,
+                    child_text(node, &["published", "updated"]),
+                    child_text(node, &["summary", "content"]),
+                )
+            }
+            _ => continue,
+        };
+
+        let Some(link) = link else {
+            continue;
+        };
+        let published_at = date.and_then(parse_date);
+
+        let mut item = NewsItem::new(
+            child_text(node, &["title"])
+                .map(strip_html)
+                .unwrap_or_default(),
+            link,
+            Some(publisher.to_string()),
+            published_at.unwrap_or(DateTime::UNIX_EPOCH),
+        );
+        item.undated = published_at.is_none();
+        item.summary = summary.map(strip_html).unwrap_or_default();
+        items.push(item);
+    }
+    Ok(items)
+}
+
+impl NewsSource for FeedSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list<'a>(
+        &'a self,
+        client: &'a http::Client,
+        symbol: &'a str,
+        target: &'a Target,
+    ) -> BoxFuture<'a, Result<Vec<NewsItem>>> {
+        Box::pin(async move {
+            let company_specific = self.url.contains("{symbol}") || self.url.contains("{company}");
+            let url = self
+                .url
+                .replace(
+                    "{symbol}",
+                    &encode_query(symbol.split('.').next().unwrap_or(symbol)),
+                )
+                .replace("{company}", &encode_query(target.name()));
+
+            let response = client.get(&url).await?;
+            if !response.status.is_success() {
+                return Err(anyhow::anyhow!("Feed request failed: {}", response.status));
+            }
+
+            let mut items = parse_feed(&response.body, &self.name)?;
+            if !company_specific {
+                let min = Config::from_env()?.news_min_relevance;
+                items.retain(|item| target.score(&item.title, &item.summary, &[]) >= min);
+            }
+            items.truncate(MAX_FEED_ITEMS);
+            Ok(items)
+        })
+    }
+}
+
+/// Yahoo first, then every feed from `SCOUT_NEWS_FEEDS` in order.
+pub fn configured_sources(config: &Config) -> Vec<Box<dyn NewsSource>> {
+    let mut sources: Vec<Box<dyn NewsSource>> = vec![Box::new(YahooNews)];
+    for (name, url) in &config.news_feeds {
+        sources.push(Box::new(FeedSource {
+            name: name.clone(),
+            url: url.clone(),
+        }));
+    }
+    sources
+}
+
+/// Compare links by host and path only, ignoring scheme, `www.`, query and fragment.
+fn normalize_link(link: &str) -> String {
+    let link = link.split(['?', '#']).next().unwrap_or(link);
+    let link = link
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.");
+    link.trim_end_matches('/').to_lowercase()
+}
+
+fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether two normalised titles name the same story. A short title is not taken
+/// as a copy of a much longer one just because its words all appear in it.
+fn same_story(a: &str, b: &str) -> bool {
+    let (a_words, b_words) = (a.split_whitespace().count(), b.split_whitespace().count());
+    a_words.min(b_words) * 2 >= a_words.max(b_words)
+        && token_set_similarity(a, b) >= DUPLICATE_TITLE_SIMILARITY
+}
+
+/// Drop items whose link or title matches an earlier item; earlier sources win.
+pub fn dedupe(items: Vec<NewsItem>) -> Vec<NewsItem> {
+    let mut kept: Vec<NewsItem> = Vec::new();
+    let mut keys: Vec<(String, String)> = Vec::new();
+
+    for item in items {
+        let link = normalize_link(&item.link);
+        let title = normalize_title(&item.title);
+        let duplicate = keys
+            .iter()
+            .any(|(l, t)| *l == link || (!title.is_empty() && same_story(t, &title)));
+        if !duplicate {
+            keys.push((link, title));
+            kept.push(item);
+        }
+    }
+    kept
+}
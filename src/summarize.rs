@@ -0,0 +1,168 @@
+use crate::agent;
+use crate::news::{self, NewsItem};
+use crate::untrusted;
+use anyhow::Result;
+
+/// Share of the context window kept free for the model's answer.
+const OUTPUT_RESERVE_DIVISOR: usize = 4;
+/// Rough size of the instructions wrapped around every prompt.
+const PROMPT_OVERHEAD_TOKENS: usize = 600;
+/// Reduce rounds before the remaining text is truncated to fit.
+const MAX_REDUCE_ROUNDS: usize = 3;
+
+/// Approximate token count; about four characters per token for English text.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Tokens of input data that fit in one prompt for a model with `context_tokens`.
+pub fn input_budget(context_tokens: usize) -> usize {
+    context_tokens
+        .saturating_sub(context_tokens / OUTPUT_RESERVE_DIVISOR)
+        .saturating_sub(PROMPT_OVERHEAD_TOKENS)
+        .max(256)
+}
+
+fn truncate_tokens(text: &str, max_tokens: usize) -> &str {
+    match text.char_indices().nth(max_tokens * 4) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+/// Split text into chunks of at most `max_tokens`, breaking on paragraphs where possible.
+pub fn chunk_text(text: &str, max_tokens: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        let mut paragraph = paragraph;
+        // A single oversized paragraph is cut at the budget
+        while estimate_tokens(paragraph) > max_tokens {
+            let head = truncate_tokens(paragraph, max_tokens);
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(head.to_string());
+            paragraph = &paragraph[head.len()..];
+        }
+
+        if !current.is_empty()
+            && estimate_tokens(&current) + estimate_tokens(paragraph) > max_tokens
+        {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Map step: summarise each article (chunk by chunk when it is too long), keeping
+/// its numbered header so the final review can still refer to it.
+async fn map_articles(
+    items: &[NewsItem],
+    budget: usize,
+    context_tokens: usize,
+) -> Result<Vec<String>> {
+    let mut summaries = Vec::new();
+
+    for (idx, item) in items.iter().enumerate() {
+        let header = news::render_header(idx + 1, item);
+        let label = format!("Article {}", idx + 1);
+        if item.note.is_some() || item.body.is_empty() {
+            let text = format!("{}{}", header, news::body_text(item));
+            summaries.push(untrusted::wrap(&label, &text) + "\n");
+            continue;
+        }
+
+        let chunks = chunk_text(
+            &item.body,
+            budget.saturating_sub(estimate_tokens(&header)).max(128),
+        );
+        println!(
+            "- Summarising article {} ({} chunk{})",
+            idx + 1,
+            chunks.len(),
+            if chunks.len() == 1 { "" } else { "s" }
+        );
+
+        let mut summary = header.clone();
+        for chunk in chunks {
+            let points =
+                agent::summarize_chunk(&format!("{header}{chunk}"), context_tokens).await?;
+            summary.push_str(&points);
+            summary.push('\n');
+        }
+        summaries.push(untrusted::wrap(&label, &summary) + "\n");
+    }
+    Ok(summaries)
+}
+
+/// Reduce step: merge summaries in groups that fit the budget until the whole set fits.
+async fn reduce_summaries(
+    mut parts: Vec<String>,
+    budget: usize,
+    context_tokens: usize,
+) -> Result<String> {
+    for _ in 0..MAX_REDUCE_ROUNDS {
+        let combined = parts.concat();
+        if estimate_tokens(&combined) <= budget {
+            return Ok(combined);
+        }
+
+        let mut groups: Vec<String> = Vec::new();
+        let mut current = String::new();
+        for part in parts {
+            if !current.is_empty() && estimate_tokens(&current) + estimate_tokens(&part) > budget {
+                groups.push(std::mem::take(&mut current));
+            }
+            current.push_str(&part);
+        }
+        if !current.is_empty() {
+            groups.push(current);
+        }
+
+        println!("- Condensing {} summary group(s)", groups.len());
+        let mut condensed = Vec::new();
+        for group in groups {
+            let group = truncate_tokens(&group, budget);
+            let points = agent::condense_summaries(group, context_tokens).await?;
+            condensed.push(untrusted::wrap("Condensed summaries", &points) + "\n");
+        }
+        parts = condensed;
+    }
+
+    Ok(truncate_tokens(&parts.concat(), budget).to_string())
+}
+
+/// Prepare the news text for the final review. Small sets are passed through whole;
+/// larger ones are summarised per article and reduced to fit the model's context.
+pub async fn prepare_news(
+    items: &[NewsItem],
+    footer: &str,
+    context_tokens: usize,
+) -> Result<String> {
+    let budget = input_budget(context_tokens);
+    let budget_left = budget.saturating_sub(estimate_tokens(footer));
+
+    let full = news::render(items);
+    if estimate_tokens(&full) <= budget_left {
+        return Ok(full + footer);
+    }
+
+    println!(
+        "- News is ~{} tokens, over the {}-token budget; summarising per article",
+        estimate_tokens(&full),
+        budget_left
+    );
+    let summaries = map_articles(items, budget_left, context_tokens).await?;
+    let reduced = reduce_summaries(summaries, budget_left, context_tokens).await?;
+    Ok(reduced + footer)
+}
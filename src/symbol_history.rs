@@ -0,0 +1,215 @@
+use crate::symbol_update;
+use crate::symbols::{self, column};
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use std::sync::OnceLock;
+
+/// Known NSE symbol changes shipped with the source; a `symbol_changes.csv` in a
+/// data directory adds to them.
+const BUNDLED_CHANGES: &str = include_str!("../data/symbol_changes.csv");
+pub const CHANGES_FILE: &str = "symbol_changes.csv";
+/// NSE's list of symbol changes, imported by `scout symbols changes`.
+const NSE_SYMBOL_CHANGES: &str =
+    "https://nsearchives.nseindia.com/content/equities/symbolchange.csv";
+/// Longest rename chain followed, guarding against cycles in the table.
+const MAX_HOPS: usize = 10;
+
+/// Header names accepted for each column, covering NSE's `symbolchange.csv` and the
+/// file written by the importer. Files without a header use NSE's column order.
+const COMPANY_COLUMNS: &[&str] = &["sm_name_of_company", "company", "company name"];
+const OLD_COLUMNS: &[&str] = &["sm_key_symbol", "old symbol", "old"];
+const NEW_COLUMNS: &[&str] = &["sm_new_symbol", "new symbol", "new"];
+const DATE_COLUMNS: &[&str] = &["sm_applicable_from", "effective date", "date"];
+
+const USAGE: &str = "Usage: scout symbols changes [PATH | URL] [--dry-run]";
+
+/// An NSE symbol replaced by another from a given date, after a rename or merger.
+#[derive(Debug, Clone)]
+pub struct SymbolChange {
+    pub company: String,
+    pub old: String,
+    pub new: String,
+    pub effective: NaiveDate,
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    ["%d-%b-%Y", "%Y-%m-%d", "%d-%m-%Y", "%d/%m/%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+/// Read symbol changes from a CSV, skipping rows without both symbols or a date.
+pub fn parse(data: &str) -> Result<Vec<SymbolChange>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+    let mut rows = reader.records();
+    let first = match rows.next() {
+        Some(row) => row?,
+        None => return Ok(Vec::new()),
+    };
+
+    let headed = column(&first, OLD_COLUMNS).is_some() && column(&first, NEW_COLUMNS).is_some();
+    let (company_col, old_col, new_col, date_col) = if headed {
+        (
+            column(&first, COMPANY_COLUMNS),
+            column(&first, OLD_COLUMNS),
+            column(&first, NEW_COLUMNS),
+            column(&first, DATE_COLUMNS),
+        )
+    } else {
+        (Some(0), Some(1), Some(2), Some(3))
+    };
+    let date_col = date_col
+        .ok_or_else(|| anyhow::anyhow!("No effective date column in the symbol changes"))?;
+
+    let mut changes = Vec::new();
+    let records = (!headed).then_some(Ok(first)).into_iter().chain(rows);
+    for row in records {
+        let row = row?;
+        let field = |i: Option<usize>| {
+            i.and_then(|i| row.get(i))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let (old, new) = (field(old_col).to_uppercase(), field(new_col).to_uppercase());
+        let Some(effective) = parse_date(&field(Some(date_col))) else {
+            continue;
+        };
+        if old.is_empty() || new.is_empty() || old == new {
+            continue;
+        }
+        changes.push(SymbolChange {
+            company: field(company_col),
+            old,
+            new,
+            effective,
+        });
+    }
+    Ok(changes)
+}
+
+fn load() -> Vec<SymbolChange> {
+    let mut changes = parse(BUNDLED_CHANGES).expect("bundled symbol_changes.csv is valid");
+    if let Some(path) = symbols::find_data_file(CHANGES_FILE) {
+        match std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| parse(&data))
+        {
+            Ok(local) => {
+                merge(&mut changes, local);
+            }
+            Err(e) => println!("- Could not read {}: {}", path.display(), e),
+        }
+    }
+    changes
+}
+
+/// Add the changes not already in `into`; returns how many were added.
+fn merge(into: &mut Vec<SymbolChange>, changes: Vec<SymbolChange>) -> usize {
+    let mut added = 0;
+    for change in changes {
+        if !into
+            .iter()
+            .any(|c| c.old == change.old && c.new == change.new && c.effective == change.effective)
+        {
+            into.push(change);
+            added += 1;
+        }
+    }
+    added
+}
+
+fn history() -> &'static [SymbolChange] {
+    static HISTORY: OnceLock<Vec<SymbolChange>> = OnceLock::new();
+    HISTORY.get_or_init(load)
+}
+
+/// The symbol an old NSE symbol trades under today, following chains of renames.
+/// The result runs from `symbol` to the latest symbol, dated by the last change.
+pub fn current(symbol: &str) -> Option<SymbolChange> {
+    let today = Utc::now().date_naive();
+    let symbol = symbol.trim().to_uppercase();
+    let mut latest: Option<&SymbolChange> = None;
+    let mut at = symbol.as_str();
+    for _ in 0..MAX_HOPS {
+        let Some(next) = history()
+            .iter()
+            .filter(|c| c.old == at && c.effective <= today)
+            .max_by_key(|c| c.effective)
+        else {
+            break;
+        };
+        if next.new == symbol {
+            break;
+        }
+        latest = Some(next);
+        at = &next.new;
+    }
+    latest.map(|last| SymbolChange {
+        old: symbol.clone(),
+        ..last.clone()
+    })
+}
+
+/// `scout symbols changes [PATH | URL] [--dry-run]`: add the entries of an exchange
+/// symbol-change file to the local `symbol_changes.csv`.
+pub async fn import(args: &[String]) -> Result<()> {
+    let mut source = NSE_SYMBOL_CHANGES;
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            flag if flag.starts_with("--") => return Err(anyhow::anyhow!("{}", USAGE)),
+            path => source = path,
+        }
+    }
+
+    let imported = parse(&symbol_update::fetch(source).await?)?;
+    if imported.is_empty() {
+        return Err(anyhow::anyhow!("{} has no symbol changes", source));
+    }
+
+    let path = symbols::local_path(CHANGES_FILE);
+    let mut changes = match std::fs::read_to_string(&path) {
+        Ok(existing) => parse(&existing)?,
+        Err(_) => Vec::new(),
+    };
+    let added = merge(&mut changes, imported);
+    println!(
+        "{} new symbol changes ({} in {})",
+        added,
+        changes.len(),
+        path.display()
+    );
+    if dry_run || added == 0 {
+        return Ok(());
+    }
+
+    changes.sort_by(|a, b| {
+        a.effective
+            .cmp(&b.effective)
+            .then_with(|| a.old.cmp(&b.old))
+    });
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["COMPANY", "OLD SYMBOL", "NEW SYMBOL", "EFFECTIVE DATE"])?;
+    for change in &changes {
+        writer.write_record([
+            change.company.as_str(),
+            change.old.as_str(),
+            change.new.as_str(),
+            &change
+                .effective
+                .format("%d-%b-%Y")
+                .to_string()
+                .to_uppercase(),
+        ])?;
+    }
+    let data = String::from_utf8(writer.into_inner()?)?;
+    symbol_update::replace_atomically(&path, &data)?;
+    println!("Updated {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use crate::symbols::{self, Listing};
+use crate::user;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// NSE's published list of listed equities.
+const NSE_EQUITY_LIST: &str = "https://nsearchives.nseindia.com/content/equities/EQUITY_L.csv";
+/// Changes listed per section before the rest are summarised as a count.
+const MAX_LISTED: usize = 20;
+/// A new list with fewer rows than this share of the current one is probably a
+/// truncated download and is refused without `--force`.
+const MIN_ROW_SHARE: f64 = 0.5;
+
+const USAGE: &str = "Usage: scout symbols update [PATH | URL] [--dry-run] [--force]";
+
+/// What changed between two symbol lists, matched by ISIN.
+struct Diff<'a> {
+    added: Vec<&'a Listing>,
+    removed: Vec<&'a Listing>,
+    /// Same ISIN under a new symbol or company name.
+    renamed: Vec<(&'a Listing, &'a Listing)>,
+}
+
+/// Rows keyed by ISIN, or by symbol for rows without one.
+fn keyed(listings: &[Listing]) -> HashMap<String, &Listing> {
+    listings
+        .iter()
+        .map(|l| {
+            let key = if l.isin.is_empty() {
+                format!("symbol:{}", l.symbol)
+            } else {
+                l.isin.clone()
+            };
+            (key, l)
+        })
+        .collect()
+}
+
+fn diff<'a>(current: &'a [Listing], new: &'a [Listing]) -> Diff<'a> {
+    let old = keyed(current);
+    let fresh = keyed(new);
+
+    let mut added: Vec<&Listing> = fresh
+        .iter()
+        .filter(|(key, _)| !old.contains_key(*key))
+        .map(|(_, l)| *l)
+        .collect();
+    let mut removed: Vec<&Listing> = old
+        .iter()
+        .filter(|(key, _)| !fresh.contains_key(*key))
+        .map(|(_, l)| *l)
+        .collect();
+    let mut renamed: Vec<(&Listing, &Listing)> = old
+        .iter()
+        .filter_map(|(key, before)| {
+            let after = fresh.get(key)?;
+            (before.symbol != after.symbol || before.name != after.name)
+                .then_some((*before, *after))
+        })
+        .collect();
+
+    added.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    removed.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    renamed.sort_by(|a, b| a.0.symbol.cmp(&b.0.symbol));
+    Diff {
+        added,
+        removed,
+        renamed,
+    }
+}
+
+fn print_section<T>(title: &str, items: &[T], line: impl Fn(&T) -> String) {
+    println!("{} ({}):", title, items.len());
+    for item in items.iter().take(MAX_LISTED) {
+        println!("  {}", line(item));
+    }
+    if items.len() > MAX_LISTED {
+        println!("  ... and {} more", items.len() - MAX_LISTED);
+    }
+}
+
+fn print_diff(diff: &Diff) {
+    print_section("Added", &diff.added, |l| {
+        format!("{:<14} {}", l.symbol, l.name)
+    });
+    print_section("Removed", &diff.removed, |l| {
+        format!("{:<14} {}", l.symbol, l.name)
+    });
+    print_section("Renamed", &diff.renamed, |(before, after)| {
+        if before.symbol == after.symbol {
+            format!("{:<14} {} -> {}", before.symbol, before.name, after.name)
+        } else {
+            format!("{} -> {} ({})", before.symbol, after.symbol, after.name)
+        }
+    });
+}
+
+/// Read the new list from a file, or download it when `source` is a URL.
+pub async fn fetch(source: &str) -> Result<String> {
+    if !source.starts_with("http://") && !source.starts_with("https://") {
+        return Ok(fs::read_to_string(source)?);
+    }
+    println!("Downloading {}", source);
+    let response = user::user_client()?.get(source).await?;
+    if !response.status.is_success() {
+        return Err(anyhow::anyhow!(
+            "Symbol list download failed: {}",
+            response.status
+        ));
+    }
+    Ok(response.body)
+}
+
+/// Write `data` next to `path` and rename it into place, so readers never see a
+/// partly written list.
+pub fn replace_atomically(path: &Path, data: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("csv.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(data.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    if let Err(e) = fs::rename(&tmp, path) {
+        fs::remove_file(&tmp).ok();
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// `scout symbols update [PATH | URL] [--dry-run] [--force]`: import a fresh NSE
+/// equity list, show what changed and replace the local `nse.csv`.
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut source = NSE_EQUITY_LIST;
+    let mut dry_run = false;
+    let mut force = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--force" => force = true,
+            flag if flag.starts_with("--") => return Err(anyhow::anyhow!("{}", USAGE)),
+            path => source = path,
+        }
+    }
+
+    let data = fetch(source).await?;
+    symbols::check_nse_header(&data)?;
+    let new = symbols::read_master(&data, "NSE", ".NS")?;
+    if new.is_empty() {
+        return Err(anyhow::anyhow!("{} has no symbols", source));
+    }
+
+    let path = symbols::local_path(symbols::NSE_FILE);
+    let existing = fs::read_to_string(&path).ok();
+    let current_data = existing.as_deref().unwrap_or(symbols::BUNDLED_NSE);
+    let current = symbols::read_master(current_data, "NSE", ".NS").unwrap_or_default();
+
+    let changes = diff(&current, &new);
+    println!(
+        "{} symbols now, {} before ({})",
+        new.len(),
+        current.len(),
+        path.display()
+    );
+    print_diff(&changes);
+
+    if (new.len() as f64) < current.len() as f64 * MIN_ROW_SHARE && !force {
+        return Err(anyhow::anyhow!(
+            "The new list has far fewer rows than the current one; rerun with --force to replace it anyway"
+        ));
+    }
+    if dry_run {
+        println!("Dry run: {} left unchanged", path.display());
+        return Ok(());
+    }
+    if existing.is_some()
+        && changes.added.is_empty()
+        && changes.removed.is_empty()
+        && changes.renamed.is_empty()
+    {
+        println!("Already up to date");
+        return Ok(());
+    }
+
+    replace_atomically(&path, &data)?;
+    println!("Updated {}", path.display());
+    Ok(())
+}
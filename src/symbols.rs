@@ -0,0 +1,668 @@
+use crate::config::Config;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use strsim::{jaro_winkler, normalized_levenshtein};
+
+/// The NSE equity list shipped with the source, used when no data directory has one.
+pub const BUNDLED_NSE: &str = include_str!("../data/nse.csv");
+pub const NSE_FILE: &str = "nse.csv";
+/// BSE scrip master ("List of Scrips" export); only read from a data directory.
+pub const BSE_FILE: &str = "bse.csv";
+/// Common names and abbreviations shipped with the source; a user `aliases.csv` in
+/// a data directory adds to and overrides them.
+const BUNDLED_ALIASES: &str = include_str!("../data/aliases.csv");
+pub const ALIAS_FILE: &str = "aliases.csv";
+
+/// Legal-form words dropped from the end of company names before matching.
+pub const LEGAL_SUFFIXES: &[&str] = &[
+    "limited",
+    "ltd",
+    "corporation",
+    "corp",
+    "inc",
+    "co",
+    "company",
+];
+
+/// Share of a query's trigrams a name must contain to be scored at all.
+const TRIGRAM_PREFILTER: f64 = 0.3;
+/// Jaro-Winkler similarity a symbol needs to be suggested for a mistyped one.
+const SYMBOL_SUGGESTION: f64 = 0.85;
+
+/// Exchanges the query parser may name, with the suffix Yahoo Finance appends to
+/// their symbols. US listings have none.
+pub const KNOWN_EXCHANGES: &[(&str, &str)] = &[
+    ("NSE", ".NS"),
+    ("BSE", ".BO"),
+    ("US", ""),
+    ("NYSE", ""),
+    ("NASDAQ", ""),
+    ("LSE", ".L"),
+    ("TSX", ".TO"),
+    ("ASX", ".AX"),
+    ("HKEX", ".HK"),
+    ("XETRA", ".DE"),
+    ("EURONEXT", ".PA"),
+    ("JPX", ".T"),
+    ("SGX", ".SI"),
+];
+
+/// Yahoo suffix for an exchange code such as "LSE"; `None` if it is not known.
+pub fn suffix_for(exchange: &str) -> Option<&'static str> {
+    KNOWN_EXCHANGES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(exchange))
+        .map(|(_, suffix)| *suffix)
+}
+
+/// Where one exchange's symbol master comes from and how Yahoo names its symbols.
+struct Master {
+    exchange: String,
+    file: String,
+    suffix: String,
+    bundled: Option<&'static str>,
+}
+
+/// NSE and BSE, then every master from `SCOUT_EXCHANGES`.
+fn masters() -> Vec<Master> {
+    let mut masters = vec![
+        Master {
+            exchange: "NSE".to_string(),
+            file: NSE_FILE.to_string(),
+            suffix: ".NS".to_string(),
+            bundled: Some(BUNDLED_NSE),
+        },
+        Master {
+            exchange: "BSE".to_string(),
+            file: BSE_FILE.to_string(),
+            suffix: ".BO".to_string(),
+            bundled: None,
+        },
+    ];
+    let configured = Config::from_env().map(|c| c.exchanges).unwrap_or_default();
+    for exchange in configured {
+        let Some(suffix) = exchange
+            .suffix
+            .clone()
+            .or_else(|| suffix_for(&exchange.code).map(String::from))
+        else {
+            println!(
+                "- Skipping {}: unknown Yahoo suffix, give it as CODE|file|suffix",
+                exchange.code
+            );
+            continue;
+        };
+        masters.push(Master {
+            exchange: exchange.code,
+            file: exchange.file,
+            suffix,
+            bundled: None,
+        });
+    }
+    masters
+}
+
+/// Header names accepted for each column, covering the NSE equity list, the BSE
+/// scrip master and plain `Symbol,Name,ISIN` files for other exchanges.
+const SYMBOL_COLUMNS: &[&str] = &["symbol", "security id", "ticker", "tidm"];
+const NAME_COLUMNS: &[&str] = &[
+    "name of company",
+    "issuer name",
+    "security name",
+    "company name",
+    "name",
+];
+const ISIN_COLUMNS: &[&str] = &["isin number", "isin no", "isin"];
+const STATUS_COLUMNS: &[&str] = &["status"];
+const SERIES_COLUMNS: &[&str] = &["series"];
+const LISTING_DATE_COLUMNS: &[&str] = &["date of listing", "listing date"];
+const FACE_VALUE_COLUMNS: &[&str] = &["face value"];
+
+/// One row of an exchange's symbol master.
+#[derive(Debug, Clone)]
+pub struct Listing {
+    pub symbol: String,
+    pub name: String,
+    pub isin: String,
+    /// Trading series such as "EQ" or "BE"; empty when the master has none.
+    pub series: String,
+    /// As written in the master, e.g. "06-OCT-2008".
+    pub listing_date: String,
+    pub face_value: String,
+    pub exchange: String,
+    /// Yahoo suffix for the exchange, e.g. ".NS".
+    pub suffix: String,
+}
+
+impl Listing {
+    /// The symbol as Yahoo Finance knows it, e.g. `RELIANCE.NS`.
+    pub fn yahoo_symbol(&self) -> String {
+        format!("{}{}", self.symbol, self.suffix)
+    }
+}
+
+/// Whether `text` is a well-formed ISIN: country code, nine alphanumerics and a
+/// Luhn check digit over the letters expanded to numbers (A = 10).
+pub fn is_isin(text: &str) -> bool {
+    let text = text.trim().to_uppercase();
+    let bytes = text.as_bytes();
+    if bytes.len() != 12
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..11].iter().all(u8::is_ascii_alphanumeric)
+        || !bytes[11].is_ascii_digit()
+    {
+        return false;
+    }
+    let digits: Vec<u32> = text
+        .chars()
+        .flat_map(|c| {
+            let n = c.to_digit(36).unwrap_or(0);
+            if n >= 10 {
+                vec![n / 10, n % 10]
+            } else {
+                vec![n]
+            }
+        })
+        .collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match i % 2 {
+            0 => d,
+            _ if d * 2 > 9 => d * 2 - 9,
+            _ => d * 2,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Lowercase, with punctuation turned into spaces, whitespace collapsed and legal
+/// forms such as "Limited", "Ltd." and "& Co" removed from the end.
+pub fn normalize(name: &str) -> String {
+    let lower = name
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '&' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>();
+    let mut words: Vec<&str> = lower.split_whitespace().collect();
+    while words.len() > 1 {
+        match words.last() {
+            Some(w) if LEGAL_SUFFIXES.contains(w) || *w == "&" || *w == "and" => {
+                words.pop();
+            }
+            _ => break,
+        }
+    }
+    words.join(" ")
+}
+
+/// Token-set similarity: 1.0 when the words of one name are a subset of the other's,
+/// otherwise how close the shared words plus each side's remaining words are.
+pub fn token_set_similarity(a: &str, b: &str) -> f64 {
+    let mut left: Vec<&str> = a.split_whitespace().collect();
+    let mut right: Vec<&str> = b.split_whitespace().collect();
+    left.sort_unstable();
+    left.dedup();
+    right.sort_unstable();
+    right.dedup();
+
+    let common: Vec<&str> = left.iter().filter(|w| right.contains(w)).copied().collect();
+    let only_left: Vec<&str> = left
+        .iter()
+        .filter(|w| !right.contains(w))
+        .copied()
+        .collect();
+    let only_right: Vec<&str> = right
+        .iter()
+        .filter(|w| !left.contains(w))
+        .copied()
+        .collect();
+
+    let base = common.join(" ");
+    let join = |rest: &[&str]| {
+        if base.is_empty() {
+            rest.join(" ")
+        } else {
+            format!("{} {}", base, rest.join(" ")).trim().to_string()
+        }
+    };
+    let with_left = join(&only_left);
+    let with_right = join(&only_right);
+
+    let mut best = normalized_levenshtein(&with_left, &with_right);
+    if !base.is_empty() {
+        best = best
+            .max(normalized_levenshtein(&base, &with_left))
+            .max(normalized_levenshtein(&base, &with_right));
+    }
+    best
+}
+
+/// Name match score from 0.0 to 1.0: the mean of Jaro-Winkler on the whole names
+/// and token-set similarity, so word order and extra words cost less.
+fn name_score(query: &str, name: &str) -> f64 {
+    (jaro_winkler(query, name) + token_set_similarity(query, name)) / 2.0
+}
+
+fn trigrams(normalized: &str) -> Vec<String> {
+    let padded: Vec<char> = format!(" {normalized} ").chars().collect();
+    let mut grams: Vec<String> = padded.windows(3).map(|w| w.iter().collect()).collect();
+    grams.sort();
+    grams.dedup();
+    grams
+}
+
+/// Searchable, in-memory view of the symbol masters, built once per process.
+pub struct SymbolIndex {
+    listings: Vec<Listing>,
+    names: Vec<String>,
+    by_symbol: HashMap<String, usize>,
+    by_isin: HashMap<String, Vec<usize>>,
+    /// Suffixes of the loaded exchanges, in load order.
+    suffixes: Vec<String>,
+    /// Series matched by name, from `SCOUT_SERIES`; empty allows every series.
+    series: Vec<String>,
+    tokens: HashMap<String, Vec<usize>>,
+    grams: HashMap<String, Vec<usize>>,
+}
+
+/// Index of the first of `names` found among the CSV headers, ignoring case.
+pub(crate) fn column(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    names.iter().find_map(|name| {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    })
+}
+
+/// Every active row of a symbol master CSV, with columns found by header name.
+pub fn read_master(data: &str, exchange: &str, suffix: &str) -> Result<Vec<Listing>> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    let headers = reader.headers()?.clone();
+    let symbol_col = column(&headers, SYMBOL_COLUMNS)
+        .ok_or_else(|| anyhow::anyhow!("No symbol column in the {exchange} list"))?;
+    let name_col = column(&headers, NAME_COLUMNS)
+        .ok_or_else(|| anyhow::anyhow!("No company name column in the {exchange} list"))?;
+    let isin_col = column(&headers, ISIN_COLUMNS);
+    let status_col = column(&headers, STATUS_COLUMNS);
+    let series_col = column(&headers, SERIES_COLUMNS);
+    let listing_date_col = column(&headers, LISTING_DATE_COLUMNS);
+    let face_value_col = column(&headers, FACE_VALUE_COLUMNS);
+
+    let mut listings = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        let field = |i: Option<usize>| {
+            i.and_then(|i| row.get(i))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let status = field(status_col);
+        if !status.is_empty() && !status.eq_ignore_ascii_case("active") {
+            continue;
+        }
+        let listing = Listing {
+            symbol: field(Some(symbol_col)),
+            name: field(Some(name_col)),
+            isin: field(isin_col).to_uppercase(),
+            series: field(series_col).to_uppercase(),
+            listing_date: field(listing_date_col),
+            face_value: field(face_value_col),
+            exchange: exchange.to_string(),
+            suffix: suffix.to_string(),
+        };
+        if listing.symbol.is_empty() || listing.name.is_empty() {
+            continue;
+        }
+        listings.push(listing);
+    }
+    Ok(listings)
+}
+
+/// Check that `data` has the header of the NSE equity list (`EQUITY_L.csv`).
+pub fn check_nse_header(data: &str) -> Result<()> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    let headers = reader.headers()?.clone();
+    let required = [
+        ("SYMBOL", SYMBOL_COLUMNS),
+        ("NAME OF COMPANY", NAME_COLUMNS),
+        ("SERIES", SERIES_COLUMNS),
+        ("DATE OF LISTING", LISTING_DATE_COLUMNS),
+        ("ISIN NUMBER", ISIN_COLUMNS),
+        ("FACE VALUE", FACE_VALUE_COLUMNS),
+    ];
+    let missing: Vec<&str> = required
+        .iter()
+        .filter(|(_, names)| column(&headers, names).is_none())
+        .map(|(label, _)| *label)
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Not an NSE equity list: missing {} column(s)",
+            missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+impl SymbolIndex {
+    fn new(series: Vec<String>) -> Self {
+        Self {
+            listings: Vec::new(),
+            names: Vec::new(),
+            by_symbol: HashMap::new(),
+            by_isin: HashMap::new(),
+            suffixes: Vec::new(),
+            series,
+            tokens: HashMap::new(),
+            grams: HashMap::new(),
+        }
+    }
+
+    /// Add every active row of a symbol master CSV; returns how many were added.
+    fn load_csv(&mut self, data: &str, exchange: &str, suffix: &str) -> Result<usize> {
+        let listings = read_master(data, exchange, suffix)?;
+        if !self.suffixes.iter().any(|s| s == suffix) {
+            self.suffixes.push(suffix.to_string());
+        }
+        let added = listings.len();
+        for listing in listings {
+            self.push(listing);
+        }
+        Ok(added)
+    }
+
+    /// Whether the listing trades in a series that name searches may return.
+    fn searchable(&self, listing: &Listing) -> bool {
+        listing.series.is_empty() || self.series.is_empty() || self.series.contains(&listing.series)
+    }
+
+    fn push(&mut self, listing: Listing) {
+        let id = self.listings.len();
+        let name = normalize(&listing.name);
+        // Other series stay reachable by symbol and ISIN but never match a name
+        if self.searchable(&listing) {
+            for token in name.split_whitespace() {
+                let ids = self.tokens.entry(token.to_string()).or_default();
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+            for gram in trigrams(&name) {
+                self.grams.entry(gram).or_default().push(id);
+            }
+        }
+        if !listing.isin.is_empty() {
+            self.by_isin
+                .entry(listing.isin.clone())
+                .or_default()
+                .push(id);
+        }
+        self.by_symbol
+            .insert(listing.yahoo_symbol().to_uppercase(), id);
+        self.names.push(name);
+        self.listings.push(listing);
+    }
+
+    /// The listing for a Yahoo symbol such as `TATASTEEL.NS`. A bare symbol is looked
+    /// up on each exchange in turn.
+    pub fn by_symbol(&self, symbol: &str) -> Option<&Listing> {
+        let symbol = symbol.to_uppercase();
+        self.by_symbol
+            .get(&symbol)
+            .or_else(|| {
+                self.suffixes
+                    .iter()
+                    .find_map(|suffix| self.by_symbol.get(&format!("{}{}", symbol, suffix)))
+            })
+            .map(|&id| &self.listings[id])
+    }
+
+    /// Whether a loaded symbol master uses this Yahoo suffix, so that a symbol
+    /// missing from the index is known not to exist there.
+    pub fn covers(&self, suffix: &str) -> bool {
+        self.suffixes.iter().any(|s| s.eq_ignore_ascii_case(suffix))
+    }
+
+    /// Listings whose symbol looks like a mistyped `symbol` on the exchange with
+    /// `suffix`, or on any loaded exchange when `suffix` is `None`, closest first.
+    pub fn similar_symbols(&self, symbol: &str, suffix: Option<&str>) -> Vec<(&Listing, f64)> {
+        let symbol = symbol.to_uppercase();
+        let mut scored: Vec<(&Listing, f64)> = self
+            .listings
+            .iter()
+            .filter(|l| {
+                suffix.is_none_or(|s| l.suffix.eq_ignore_ascii_case(s)) && self.searchable(l)
+            })
+            .map(|l| (l, jaro_winkler(&symbol, &l.symbol.to_uppercase())))
+            .filter(|(_, score)| *score >= SYMBOL_SUGGESTION)
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| a.0.symbol.cmp(&b.0.symbol))
+        });
+        scored
+    }
+
+    /// Every listing of the security with this ISIN, across exchanges.
+    pub fn listings_for(&self, isin: &str) -> Vec<&Listing> {
+        self.by_isin
+            .get(&isin.trim().to_uppercase())
+            .into_iter()
+            .flatten()
+            .map(|&id| &self.listings[id])
+            .collect()
+    }
+
+    /// The listing for an ISIN, on `preferred` exchange when listed there and in an
+    /// allowed series where possible.
+    pub fn by_isin(&self, isin: &str, preferred: &str) -> Option<&Listing> {
+        let listings = self.listings_for(isin);
+        listings
+            .iter()
+            .copied()
+            .filter(|l| self.searchable(l))
+            .min_by_key(|l| !l.exchange.eq_ignore_ascii_case(preferred))
+            .or_else(|| listings.first().copied())
+    }
+
+    /// The same company's listing on `exchange` when it is dual-listed (matched by
+    /// ISIN), otherwise the listing itself.
+    fn on_exchange(&self, id: usize, exchange: &str) -> usize {
+        let isin = &self.listings[id].isin;
+        self.by_isin
+            .get(isin)
+            .into_iter()
+            .flatten()
+            .copied()
+            .find(|&other| self.listings[other].exchange.eq_ignore_ascii_case(exchange))
+            .unwrap_or(id)
+    }
+
+    /// `listing`, moved to `exchange` if the company is also listed there.
+    pub fn prefer<'a>(&'a self, listing: &'a Listing, exchange: &str) -> &'a Listing {
+        match self.by_symbol.get(&listing.yahoo_symbol().to_uppercase()) {
+            Some(&id) => &self.listings[self.on_exchange(id, exchange)],
+            None => listing,
+        }
+    }
+
+    /// Listings sharing a word or enough trigrams with the normalized query.
+    fn candidates(&self, query: &str) -> Vec<usize> {
+        let mut hits: HashMap<usize, usize> = HashMap::new();
+        let grams = trigrams(query);
+        for gram in &grams {
+            for &id in self.grams.get(gram).into_iter().flatten() {
+                *hits.entry(id).or_default() += 1;
+            }
+        }
+        let needed = ((grams.len() as f64 * TRIGRAM_PREFILTER).ceil() as usize).max(1);
+        let mut ids: Vec<usize> = hits
+            .into_iter()
+            .filter(|(_, n)| *n >= needed)
+            .map(|(id, _)| id)
+            .collect();
+        for token in query.split_whitespace() {
+            ids.extend(self.tokens.get(token).into_iter().flatten());
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Companies matching a name, ranked by name score, best first. Dual-listed
+    /// companies appear once, on `preferred` exchange when listed there. With
+    /// `exchange`, only listings using that exchange's Yahoo suffix are kept.
+    pub fn ranked(
+        &self,
+        company: &str,
+        preferred: &str,
+        exchange: Option<&str>,
+    ) -> Vec<(&Listing, f64)> {
+        let query = normalize(company);
+        let preferred = exchange.unwrap_or(preferred);
+        let suffix = exchange.and_then(suffix_for);
+        let mut best: HashMap<usize, f64> = HashMap::new();
+        for id in self.candidates(&query) {
+            let score = name_score(&query, &self.names[id]);
+            let id = self.on_exchange(id, preferred);
+            if exchange.is_some_and(|code| {
+                let listing = &self.listings[id];
+                !listing.exchange.eq_ignore_ascii_case(code)
+                    && suffix.is_none_or(|s| listing.suffix != s)
+            }) {
+                continue;
+            }
+            let entry = best.entry(id).or_insert(score);
+            *entry = entry.max(score);
+        }
+
+        let mut scored: Vec<(&Listing, f64)> = best
+            .into_iter()
+            .map(|(id, score)| (&self.listings[id], score))
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| a.0.symbol.cmp(&b.0.symbol))
+        });
+        scored
+    }
+}
+
+/// A possible resolution of a company name, as offered to the user.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// Yahoo symbol, e.g. `TATASTEEL.NS`.
+    pub symbol: String,
+    pub name: String,
+    pub score: f64,
+}
+
+/// Directories searched for symbol files: `SCOUT_DATA_DIR`, `./data`, then `data`
+/// next to the executable.
+fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = Config::from_env().ok().and_then(|c| c.data_dir) {
+        dirs.push(dir);
+    }
+    dirs.push(PathBuf::from("data"));
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(PathBuf::from))
+    {
+        dirs.push(exe_dir.join("data"));
+    }
+    dirs
+}
+
+/// Where a downloaded copy of `file` is saved: over the copy in use, else in the
+/// first data directory.
+pub fn local_path(file: &str) -> PathBuf {
+    find_data_file(file).unwrap_or_else(|| data_dirs()[0].join(file))
+}
+
+/// First existing copy of `file` in the data directories.
+pub fn find_data_file(file: &str) -> Option<PathBuf> {
+    data_dirs()
+        .into_iter()
+        .map(|dir| dir.join(file))
+        .find(|path| path.is_file())
+}
+
+fn load() -> SymbolIndex {
+    let series = Config::from_env().map(|c| c.series).unwrap_or_default();
+    let mut index = SymbolIndex::new(series);
+    for master in masters() {
+        let mut loaded = false;
+        if let Some(path) = find_data_file(&master.file) {
+            match std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| index.load_csv(&data, &master.exchange, &master.suffix))
+            {
+                Ok(0) => println!("- {} has no symbols", path.display()),
+                Ok(_) => loaded = true,
+                Err(e) => println!("- Could not read {}: {}", path.display(), e),
+            }
+        }
+        if !loaded && let Some(bundled) = master.bundled {
+            index
+                .load_csv(bundled, &master.exchange, &master.suffix)
+                .expect("bundled symbol list is valid");
+        }
+    }
+    index
+}
+
+/// The process-wide symbol index, loaded on first use.
+pub fn index() -> &'static SymbolIndex {
+    static INDEX: OnceLock<SymbolIndex> = OnceLock::new();
+    INDEX.get_or_init(load)
+}
+
+/// Parse `ALIAS,SYMBOL` rows into normalized alias -> upper-case symbol pairs.
+fn parse_aliases(data: &str, into: &mut HashMap<String, String>) -> Result<()> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    for row in reader.records() {
+        let row = row?;
+        let (Some(alias), Some(symbol)) = (row.get(0), row.get(1)) else {
+            continue;
+        };
+        let alias = normalize(alias);
+        if !alias.is_empty() && !symbol.trim().is_empty() {
+            into.insert(alias, symbol.trim().to_uppercase());
+        }
+    }
+    Ok(())
+}
+
+fn load_aliases() -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    parse_aliases(BUNDLED_ALIASES, &mut aliases).expect("bundled aliases.csv is valid");
+    if let Some(path) = find_data_file(ALIAS_FILE)
+        && let Err(e) = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| parse_aliases(&data, &mut aliases))
+    {
+        println!("- Could not read {}: {}", path.display(), e);
+    }
+    aliases
+}
+
+/// The symbol an alias such as "RIL" or "L&T" stands for, if one is defined.
+pub fn alias(name: &str) -> Option<&'static str> {
+    static ALIASES: OnceLock<HashMap<String, String>> = OnceLock::new();
+    ALIASES
+        .get_or_init(load_aliases)
+        .get(&normalize(name))
+        .map(String::as_str)
+}
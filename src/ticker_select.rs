@@ -0,0 +1,106 @@
+use crate::symbols::Candidate;
+use anyhow::Result;
+use crossterm::{
+    cursor::MoveTo,
+    event::{Event, KeyCode, KeyEventKind, read},
+    execute,
+    style::{Color, ResetColor, SetForegroundColor},
+    terminal::{Clear, ClearType, disable_raw_mode, enable_raw_mode},
+};
+use std::io::{self, Write};
+
+/// Candidates scoring within this much of the best one make a name ambiguous.
+const CLOSE_MARGIN: f64 = 0.03;
+
+/// Whether the top candidates are too close to pick one without asking.
+pub fn is_ambiguous(candidates: &[Candidate]) -> bool {
+    match candidates {
+        [first, second, ..] => first.score - second.score < CLOSE_MARGIN,
+        _ => false,
+    }
+}
+
+fn draw_candidate_menu(company: &str, candidates: &[Candidate], selection: usize) -> Result<()> {
+    let mut stdout = io::stdout();
+    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+
+    let mut row = 0u16;
+
+    execute!(stdout, SetForegroundColor(Color::Blue))?;
+    execute!(stdout, MoveTo(0, row))?;
+    writeln!(stdout, "Select company")?;
+    row += 1;
+    execute!(stdout, MoveTo(0, row))?;
+    writeln!(stdout, "\"{}\" matches several listed companies.", company)?;
+    row += 2;
+
+    execute!(stdout, ResetColor)?;
+    for (i, candidate) in candidates.iter().enumerate() {
+        let marker = if i == selection { "> " } else { "  " };
+        let line = format!(
+            "{}{}. {:<16} {} ({:.2})",
+            marker,
+            i + 1,
+            candidate.symbol,
+            candidate.name,
+            candidate.score
+        );
+        execute!(stdout, MoveTo(0, row))?;
+        if i == selection {
+            execute!(stdout, SetForegroundColor(Color::Blue))?;
+        }
+        writeln!(stdout, "{}", line)?;
+        execute!(stdout, ResetColor)?;
+        row += 1;
+    }
+
+    row += 1;
+    execute!(stdout, SetForegroundColor(Color::DarkGrey))?;
+    execute!(stdout, MoveTo(0, row))?;
+    writeln!(stdout, "Enter to confirm, esc to cancel the query")?;
+    execute!(stdout, ResetColor)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn select(company: &str, candidates: &[Candidate]) -> Result<Option<usize>> {
+    let mut selection = 0usize;
+    loop {
+        draw_candidate_menu(company, candidates, selection)?;
+        match read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Up => {
+                    selection = if selection == 0 {
+                        candidates.len().saturating_sub(1)
+                    } else {
+                        selection - 1
+                    };
+                }
+                KeyCode::Down => {
+                    selection = if selection >= candidates.len().saturating_sub(1) {
+                        0
+                    } else {
+                        selection + 1
+                    };
+                }
+                KeyCode::Enter => return Ok(Some(selection)),
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Let the user pick one of `candidates` with the arrow keys. Returns `None` if the
+/// menu was dismissed.
+pub fn pick(company: &str, candidates: &[Candidate]) -> Result<Option<Candidate>> {
+    // Queries run in cooked mode; the menu needs raw key events
+    enable_raw_mode()?;
+    let choice = select(company, candidates);
+    disable_raw_mode()?;
+
+    let mut stdout = io::stdout();
+    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+    Ok(choice?.map(|i| candidates[i].clone()))
+}
@@ -1,44 +1,309 @@
+use crate::config::Config;
+use crate::consent::{self, PageKind};
 use crate::display;
+use crate::extract;
+use crate::http;
 use crate::income_statement;
+use crate::news::NewsItem;
+use crate::relevance::Target;
+use crate::sources;
+use crate::symbol_history::{self, SymbolChange};
+use crate::symbols::{self, Candidate, Listing};
+use crate::untrusted;
 use anyhow::Ok;
 use anyhow::Result;
-use csv::Reader;
-use scraper::Html;
-use scraper::Selector;
-use std::fmt::Write;
-use std::fs::File;
-use strsim::jaro_winkler;
-
-pub fn find_ticker(company: &str) -> Option<String> {
-    let file = File::open("data/nse.csv").ok()?;
-    let mut rdr = Reader::from_reader(file);
-
-    let mut best = None;
-    let mut best_score = 0.0;
-
-    for row in rdr.records() {
-        let r = row.ok()?;
-        let symbol = &r[0];
-        let name = &r[1];
-
-        let score = jaro_winkler(&company.to_lowercase(), &name.to_lowercase());
-
-        if score > best_score {
-            best_score = score;
-            best = Some(symbol.to_string());
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+
+/// Lowest score a name match needs to be offered at all.
+const MIN_MATCH_SCORE: f64 = 0.80;
+/// Most candidates returned for one company name.
+const MAX_CANDIDATES: usize = 5;
+/// Bare tickers shorter than this are only taken as typos of a symbol of the same
+/// length; short US tickers are often one letter away from an Indian symbol.
+const MIN_TYPO_CHECK_LEN: usize = 5;
+
+fn candidate(listing: &Listing, score: f64) -> Candidate {
+    Candidate {
+        symbol: listing.yahoo_symbol(),
+        name: listing.name.clone(),
+        score,
+    }
+}
+
+/// The listing an alias from `aliases.csv` points at, as a certain match.
+pub fn find_alias(name: &str) -> Option<Candidate> {
+    let config = Config::from_env().ok()?;
+    let index = symbols::index();
+    let listing = index.by_symbol(symbols::alias(name)?)?;
+    Some(candidate(
+        index.prefer(listing, &config.preferred_exchange),
+        1.0,
+    ))
+}
+
+/// The listing for an ISIN such as `INE002A01018`, on the preferred exchange.
+pub fn find_isin(isin: &str) -> Option<Candidate> {
+    if !symbols::is_isin(isin) {
+        return None;
+    }
+    let preferred = Config::from_env()
+        .map(|c| c.preferred_exchange)
+        .unwrap_or_default();
+    symbols::index()
+        .by_isin(isin, &preferred)
+        .map(|listing| candidate(listing, 1.0))
+}
+
+/// First ISIN written in `text` that is in the symbol masters.
+pub fn isin_in(text: &str) -> Option<Candidate> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .find_map(find_isin)
+}
+
+/// The change that replaced an old NSE symbol, after telling the user about it.
+fn renamed(symbol: &str) -> Option<SymbolChange> {
+    let change = symbol_history::current(symbol)?;
+    println!(
+        "{} is now {} (since {})",
+        change.old,
+        change.new,
+        change.effective.format("%Y-%m-%d")
+    );
+    Some(change)
+}
+
+/// The listing for a name that is an old NSE symbol, such as "Zomato". Symbols
+/// still in the current master are not old ones, whatever the history says.
+pub fn find_renamed(name: &str) -> Option<Candidate> {
+    let index = symbols::index();
+    if name.split_whitespace().count() != 1 || index.by_symbol(name.trim()).is_some() {
+        return None;
+    }
+    let change = renamed(name)?;
+    let symbol = format!("{}.NS", change.new);
+    Some(match index.by_symbol(&symbol) {
+        Some(listing) => candidate(listing, 1.0),
+        None => Candidate {
+            symbol,
+            name: change.company,
+            score: 1.0,
+        },
+    })
+}
+
+/// Symbols whose company name matches `company`, best first, on the preferred
+/// exchange where the company is listed there, or only on `exchange` when the query
+/// named one. ISINs, aliases and old symbols are checked before fuzzy matching.
+pub fn find_ticker(company: &str, exchange: Option<&str>) -> Vec<Candidate> {
+    if let Some(candidate) = find_isin(company)
+        .or_else(|| find_alias(company))
+        .or_else(|| find_renamed(company))
+    {
+        return vec![candidate];
+    }
+    let preferred = Config::from_env()
+        .map(|c| c.preferred_exchange)
+        .unwrap_or_default();
+    symbols::index()
+        .ranked(company, &preferred, exchange)
+        .into_iter()
+        .take_while(|(_, score)| *score > MIN_MATCH_SCORE)
+        .take(MAX_CANDIDATES)
+        .map(|(listing, score)| candidate(listing, score))
+        .collect()
+}
+
+/// Split a ticker into its symbol and a known Yahoo suffix, e.g. ("TCS", ".NS").
+pub fn split_suffix(ticker: &str) -> (&str, &str) {
+    match ticker.rfind('.') {
+        Some(dot)
+            if symbols::KNOWN_EXCHANGES
+                .iter()
+                .any(|(_, known)| known.eq_ignore_ascii_case(&ticker[dot..])) =>
+        {
+            ticker.split_at(dot)
         }
+        _ => (ticker, ""),
     }
+}
 
-    if best_score > 0.80 {
-        best.map(|s| format!("{}.NS", s))
+/// Turn a ticker as the user wrote it into a Yahoo symbol. NSE symbols missing from
+/// the current master are replaced by their renamed one. A known suffix is kept;
+/// otherwise the named exchange's suffix is added, or the listing found in the
+/// symbol masters is used. A ticker found nowhere is left without a suffix rather
+/// than guessed onto the preferred exchange, so "AAPL" stays "AAPL".
+pub fn resolve_symbol(ticker: &str, exchange: Option<&str>) -> String {
+    if let Some(candidate) = find_isin(ticker) {
+        return candidate.symbol;
+    }
+    let index = symbols::index();
+    let mut ticker = ticker.trim().to_uppercase();
+    let (base, suffix) = split_suffix(&ticker);
+    let on_nse = exchange.is_none_or(|e| e.eq_ignore_ascii_case("NSE"));
+    // A bare ticker without an exchange is current if it is listed anywhere
+    let listed = if suffix.is_empty() && exchange.is_none() {
+        index.by_symbol(&ticker).is_some()
     } else {
-        None
+        index.by_symbol(&format!("{base}.NS")).is_some()
+    };
+    if matches!(suffix, "" | ".NS")
+        && on_nse
+        && !listed
+        && let Some(change) = renamed(base)
+    {
+        ticker = format!("{}{}", change.new, suffix);
+    }
+    if !split_suffix(&ticker).1.is_empty() {
+        return ticker;
+    }
+    if let Some(suffix) = exchange.and_then(symbols::suffix_for) {
+        return format!("{ticker}{suffix}");
+    }
+
+    let preferred = Config::from_env()
+        .map(|c| c.preferred_exchange)
+        .unwrap_or_default();
+    match index.by_symbol(&ticker) {
+        Some(listing) => index.prefer(listing, &preferred).yahoo_symbol(),
+        None => ticker,
+    }
+}
+
+/// Whether Yahoo Finance has a quote for `symbol`, from a one-day chart request.
+async fn quote_exists(client: &http::Client, symbol: &str) -> Result<bool> {
+    let url = format!(
+        "https://query1.finance.yahoo.com/v8/finance/chart/{}?range=1d&interval=1d",
+        symbol
+    );
+    let response = client.get(&url).await?;
+    if response.status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+    if !response.status.is_success() {
+        return Err(anyhow::anyhow!("quote request failed: {}", response.status));
+    }
+    let body: serde_json::Value = serde_json::from_str(&response.body)?;
+    Ok(body["chart"]["result"]
+        .as_array()
+        .is_some_and(|r| !r.is_empty()))
+}
+
+/// Whether a bare ticker found in no master is a typo of `symbol`: one edit apart
+/// with the same first letter, and for short tickers the same length, so "TATASTEL"
+/// and "INFI" are caught but "MSFT" is not taken for "ISFT" nor "NFLX" for "NFL".
+fn looks_mistyped(written: &str, symbol: &str) -> bool {
+    let (written, symbol) = (written.to_uppercase(), symbol.to_uppercase());
+    strsim::osa_distance(&written, &symbol) <= 1
+        && written.chars().next() == symbol.chars().next()
+        && (written.len() >= MIN_TYPO_CHECK_LEN || written.len() == symbol.len())
+}
+
+/// Check that a ticker exists before anything is fetched for it. Tickers in the
+/// symbol masters pass; others are checked with Yahoo when `SCOUT_QUOTE_CHECK` is
+/// on, and rejected with "did you mean" suggestions when their exchange's master
+/// is loaded. A ticker `resolve_symbol` left without a suffix is compared with every
+/// master and rejected when it looks like a mistyped symbol, e.g. "TATASTEL"; one
+/// close to nothing ("AAPL") passes, as do tickers on exchanges without a master.
+pub async fn validate_ticker(client: &http::Client, ticker: &str) -> Result<()> {
+    let index = symbols::index();
+    if index.by_symbol(ticker).is_some() {
+        return Ok(());
+    }
+    let (base, suffix) = split_suffix(ticker);
+    let exchange = (!suffix.is_empty()).then_some(suffix);
+    let suggestions: Vec<String> = index
+        .similar_symbols(base, exchange)
+        .into_iter()
+        .filter(|(listing, _)| exchange.is_some() || looks_mistyped(base, &listing.symbol))
+        .take(MAX_CANDIDATES)
+        .map(|(listing, _)| format!("{} ({})", listing.yahoo_symbol(), listing.name))
+        .collect();
+    // Whether a miss can be told apart from a symbol no loaded master lists
+    let checkable = match exchange {
+        Some(suffix) => index.covers(suffix),
+        None => !suggestions.is_empty(),
+    };
+
+    if Config::from_env()?.quote_check {
+        match quote_exists(client, ticker).await {
+            std::result::Result::Ok(true) => {
+                println!(
+                    "- {} is not in the symbol masters but Yahoo has a quote",
+                    ticker
+                );
+                return Ok(());
+            }
+            std::result::Result::Ok(false) => {}
+            Err(e) => {
+                println!("- Could not check {} with Yahoo: {}", ticker, e);
+                if !checkable {
+                    return Ok(());
+                }
+            }
+        }
+    } else if !checkable {
+        return Ok(());
+    }
+
+    if suggestions.is_empty() {
+        return Err(anyhow::anyhow!("Unknown ticker: {}", ticker));
+    }
+    Err(anyhow::anyhow!(
+        "Unknown ticker: {}. Did you mean {}?",
+        ticker,
+        suggestions.join(", ")
+    ))
+}
+
+/// Master-data rows for a ticker or ISIN, the matched listing first, followed by
+/// the same security on other exchanges.
+pub fn symbol_info(query: &str) -> Vec<Listing> {
+    let query = query.trim();
+    let index = symbols::index();
+    let listing = match find_isin(query) {
+        Some(candidate) => index.by_symbol(&candidate.symbol),
+        None => index.by_symbol(query).or_else(|| {
+            let (base, suffix) = split_suffix(query);
+            index.by_symbol(&format!("{}{}", renamed(base)?.new, suffix))
+        }),
+    };
+    let Some(listing) = listing else {
+        return Vec::new();
+    };
+    let mut rows = vec![listing.clone()];
+    rows.extend(
+        index
+            .listings_for(&listing.isin)
+            .into_iter()
+            .filter(|other| other.yahoo_symbol() != listing.yahoo_symbol())
+            .cloned(),
+    );
+    rows
+}
+
+/// The listing for a symbol written exactly, e.g. `INFY` or `TCS.NS`. A bare symbol
+/// is taken on the preferred exchange when the company is listed there.
+pub fn find_symbol(symbol: &str) -> Option<Candidate> {
+    let index = symbols::index();
+    let mut listing = index.by_symbol(symbol.trim())?;
+    if split_suffix(&symbol.trim().to_uppercase()).1.is_empty() {
+        let preferred = Config::from_env()
+            .map(|c| c.preferred_exchange)
+            .unwrap_or_default();
+        listing = index.prefer(listing, &preferred);
     }
+    Some(candidate(listing, 1.0))
+}
+
+/// Look up the listed company name for a symbol such as `TATASTEEL.NS`.
+pub fn find_company(symbol: &str) -> Option<String> {
+    symbols::index().by_symbol(symbol).map(|l| l.name.clone())
 }
 
 pub async fn get_financials(
     symbol: &String,
-    client: &reqwest::Client,
+    client: &http::Client,
     finance: &str,
 ) -> Result<String> {
     let mut link: String = String::new();
@@ -62,23 +327,20 @@ pub async fn get_financials(
         }
     }
 
-    let response = client.get(&link).send().await?;
+    let response = client.get(&link).await?;
 
-    if !response.status().is_success() {
-        println!("Could not fetch data: HTTP {}", response.status());
-        return Err(anyhow::anyhow!("Request failed: {}", response.status()));
+    if !response.status.is_success() {
+        println!("Could not fetch data: HTTP {}", response.status);
+        return Err(anyhow::anyhow!("Request failed: {}", response.status));
     }
 
-    let body = response.text().await?;
+    let body = response.body;
 
     if let Some((headers, rows)) = income_statement::scrape_financials_table(&body) {
         let period_headers: Vec<String> = headers.into_iter().skip(1).collect();
         println!("- Read {title}");
-        return Ok(display::print_scraped_table(
-            &title.as_str(),
-            &period_headers,
-            &rows,
-        ));
+        let table = display::print_scraped_table(title.as_str(), &period_headers, &rows);
+        return Ok(format!("{table}Source: {link}\n"));
     }
 
     Err(anyhow::anyhow!(
@@ -86,106 +348,225 @@ pub async fn get_financials(
     ))
 }
 
-pub async fn get_news(client: &reqwest::Client, symbol: &String) -> Result<String> {
-    let yf_client = yfinance_rs::YfClient::default();
-    let ticker = yfinance_rs::Ticker::new(&yf_client, symbol);
-
-    let news = ticker.news().await?;
-    let mut news_combined = String::new();
-
-    for (idx, article) in news.into_iter().enumerate() {
-        if let Some(link) = article.link {
-            let title = article.title;
-
-            writeln!(&mut news_combined, "========== Article {} ==========", idx + 1).ok();
-            writeln!(&mut news_combined, "Title: {}", title).ok();
-            writeln!(&mut news_combined, "Link:  {}", link).ok();
-            writeln!(&mut news_combined).ok();
-
-            let response = client.get(&link).send().await?;
-            if !response.status().is_success() {
-                writeln!(
-                    &mut news_combined,
-                    "[ERROR] Could not fetch article body: HTTP {}",
-                    response.status()
-                )
-                .ok();
-                writeln!(&mut news_combined).ok();
-                continue;
-            } else {
-                println!("fetched data for {title} - {link}");
-            }
+/// Longest news window, so "last 1000000 years" cannot overflow the date math.
+const MAX_WINDOW_DAYS: i64 = 3650;
 
-            let body_html = response.text().await?;
-            let document = Html::parse_document(&body_html);
-
-            let container_selector = Selector::parse("div.article.yf-1qeh9w1").unwrap();
-            let text_selector =
-                Selector::parse("p, h1, h2, h3, h4, h5, h6, li, blockquote").unwrap();
-
-            let mut article_text = String::new();
-
-            let is_boilerplate = |t: &str| {
-                let l = t.to_lowercase();
-                let stop_markers = [
-                    "go to accessibility shortcuts",
-                    "share",
-                    "comments",
-                    "read more",
-                    "additional sources",
-                    "edited by",
-                    "the big question",
-                ];
-                stop_markers.iter().any(|m| l.contains(m))
-            };
-
-            if let Some(container) = document.select(&container_selector).next() {
-                for element in container.select(&text_selector) {
-                    let text = element.text().collect::<Vec<_>>().join(" ");
-                    let text = text.trim();
-
-                    if text.len() < 40 {
-                        continue;
-                    }
-                    if is_boilerplate(text) {
-                        break;
-                    }
-
-                    if !article_text.is_empty() {
-                        article_text.push_str("\n\n");
-                    }
-                    article_text.push_str(text);
-                }
-            } else {
-                let fallback_selector = Selector::parse(
-                    "article, main, p, h1, h2, h3, h4, h5, h6, li, blockquote",
-                )
-                .unwrap();
-
-                for element in document.select(&fallback_selector) {
-                    let text = element.text().collect::<Vec<_>>().join(" ");
-                    let text = text.trim();
-
-                    if text.len() < 40 {
-                        continue;
-                    }
-                    if is_boilerplate(text) {
-                        break;
-                    }
-
-                    if !article_text.is_empty() {
-                        article_text.push_str("\n\n");
-                    }
-                    article_text.push_str(text);
-                }
+/// Read a news window such as "last 7 days", "past 2 weeks", "this month" or "today"
+/// from the user query, returned as a number of days (at most ten years).
+pub fn parse_news_window(input: &str) -> Option<i64> {
+    let lower = input.to_lowercase();
+    if lower.contains("today") {
+        return Some(1);
+    }
+    if lower.contains("this week") {
+        return Some(7);
+    }
+    if lower.contains("this month") {
+        return Some(30);
+    }
+
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    for (i, word) in words.iter().enumerate() {
+        if *word != "last" && *word != "past" {
+            continue;
+        }
+        let (count, unit) = match words.get(i + 1).map(|w| w.parse::<i64>()) {
+            Some(std::result::Result::Ok(n)) => (n, words.get(i + 2)),
+            _ => (1, words.get(i + 1)),
+        };
+        let days = match unit.map(|u| u.trim_end_matches('s')) {
+            Some("day") => 1,
+            Some("week") => 7,
+            Some("month") => 30,
+            Some("year") => 365,
+            _ => continue,
+        };
+        return Some(
+            count
+                .max(1)
+                .checked_mul(days)
+                .map_or(MAX_WINDOW_DAYS, |d| d.min(MAX_WINDOW_DAYS)),
+        );
+    }
+    None
+}
+
+/// Start of a window of `days` days ending at `now`; `None` if it cannot be
+/// represented.
+pub fn window_cutoff(now: DateTime<Utc>, days: i64) -> Option<DateTime<Utc>> {
+    now.checked_sub_signed(chrono::Duration::try_days(days)?)
+}
+
+/// Fetch the news feed for `symbol`, newest first, optionally limited to articles
+/// published from `cutoff` on, scrape every article body and score it for relevance.
+pub async fn get_news(
+    client: &http::Client,
+    symbol: &str,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<Vec<NewsItem>> {
+    let config = Config::from_env()?;
+    let target = Target::new(symbol);
+    let sources = sources::configured_sources(&config);
+
+    let listings = futures::future::join_all(
+        sources
+            .iter()
+            .map(|source| source.list(client, symbol, &target)),
+    )
+    .await;
+
+    let mut news: Vec<NewsItem> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+    for (source, listing) in sources.iter().zip(listings) {
+        match listing {
+            std::result::Result::Ok(items) => news.extend(items),
+            Err(e) => {
+                println!("- News source {} failed: {}", source.name(), e);
+                failures.push(format!("{}: {}", source.name(), e));
             }
+        }
+    }
+    if failures.len() == sources.len() {
+        return Err(anyhow::anyhow!(
+            "All news sources failed ({})",
+            failures.join("; ")
+        ));
+    }
+
+    let mut news = sources::dedupe(news);
+    if let Some(cutoff) = cutoff {
+        news.retain(|item| item.undated || item.published_at >= cutoff);
+    }
+
+    let mut items: Vec<NewsItem> = stream::iter(news)
+        .map(|item| fetch_article(client, item))
+        .buffered(config.news_concurrency.max(1))
+        .collect()
+        .await;
 
-            let article_text = article_text.trim();
-            if !article_text.is_empty() {
-                writeln!(&mut news_combined, "{}\n", article_text).ok();
+    // Entries the listing left without a title or date are only kept when their page
+    // supplied one, and then face the same window
+    items.retain(|item| {
+        if item.undated || item.title.is_empty() {
+            println!("- Skipped article without a title or date: {}", item.link);
+            return false;
+        }
+        cutoff.is_none_or(|cutoff| item.published_at >= cutoff)
+    });
+    items.sort_by_key(|item| std::cmp::Reverse(item.published_at));
+
+    for item in items.iter_mut() {
+        let (title, title_flagged) = untrusted::sanitize(&item.title);
+        let (body, body_flagged) = untrusted::sanitize(&item.body);
+        item.title = title;
+        item.body = body;
+        item.suspicious = title_flagged || body_flagged;
+        if item.suspicious {
+            println!("- Removed possible prompt injection from: {}", item.title);
+        }
+
+        item.relevance = target.score(&item.title, &item.body, &item.tickers);
+        item.low_relevance = item.relevance < config.news_min_relevance;
+    }
+
+    if config.drop_irrelevant_news {
+        let (kept, dropped): (Vec<_>, Vec<_>) =
+            items.into_iter().partition(|item| !item.low_relevance);
+        for item in &dropped {
+            println!(
+                "- Skipped article not about {} ({:.2}): {}",
+                target.name(),
+                item.relevance,
+                item.title
+            );
+        }
+        items = kept;
+    }
+
+    Ok(items)
+}
+
+/// Fetch one article body into `item`. Failures are recorded in `item.note` instead
+/// of aborting the other articles.
+async fn fetch_article(client: &http::Client, mut item: NewsItem) -> NewsItem {
+    let mut response = match client.get(&item.link).await {
+        std::result::Result::Ok(response) => response,
+        Err(e) => {
+            item.note = Some(format!("[ERROR] Could not fetch article body: {}", e));
+            return item;
+        }
+    };
+    if !response.status.is_success() {
+        item.note = Some(format!(
+            "[ERROR] Could not fetch article body: HTTP {}",
+            response.status
+        ));
+        return item;
+    }
+
+    let consent_wall = "[UNAVAILABLE] Article is behind a cookie-consent page".to_string();
+    if consent::classify(&response.url, &response.body) == PageKind::ConsentWall {
+        match complete_consent(client, &item.link, &response).await {
+            Some(answered) => response = answered,
+            None => {
+                item.note = Some(consent_wall);
+                return item;
             }
         }
     }
 
-    Ok(news_combined)
+    match consent::classify(&response.url, &response.body) {
+        PageKind::Article => {}
+        PageKind::ConsentWall => {
+            item.note = Some(consent_wall);
+            return item;
+        }
+        PageKind::Paywall => {
+            item.note = Some("[UNAVAILABLE] Article is behind a paywall".to_string());
+            return item;
+        }
+    }
+    println!("fetched data for {} - {}", item.title, item.link);
+
+    let article = extract::extract_article(&response.body);
+    if consent::is_consent_text(&article.body) {
+        item.note = Some(consent_wall);
+        return item;
+    }
+    // Feeds sometimes leave out the title or date the page itself carries
+    if item.title.is_empty() {
+        item.title = article.title.unwrap_or_default();
+    }
+    if item.undated
+        && let Some(published_at) = article.published.as_deref().and_then(sources::parse_date)
+    {
+        item.published_at = published_at;
+        item.undated = false;
+    }
+    item.author = article.author;
+    item.tickers = article.tickers;
+    item.body = if article.body.is_empty() {
+        std::mem::take(&mut item.summary)
+    } else {
+        article.body
+    };
+    item
+}
+
+/// Answer a consent page through its own form with the cookie-enabled client, then
+/// return the article page it leads to.
+async fn complete_consent(
+    client: &http::Client,
+    link: &str,
+    page: &http::Response,
+) -> Option<http::Response> {
+    let (action, fields) = consent::consent_form(&page.url, &page.body)?;
+    let answered = client.post_form(&action, &fields).await.ok()?;
+    if consent::classify(&answered.url, &answered.body) == PageKind::Article {
+        return Some(answered);
+    }
+    client.get(link).await.ok()
 }
@@ -1,5 +1,6 @@
+use crate::http;
 use reqwest::header::{ACCEPT, ACCEPT_LANGUAGE, HeaderMap, HeaderValue, REFERER, USER_AGENT};
-pub fn user_client() -> anyhow::Result<reqwest::Client> {
+pub fn user_client() -> anyhow::Result<http::Client> {
     let mut headers = HeaderMap::new();
 
     // Mimic real browser headers
@@ -20,13 +21,10 @@ pub fn user_client() -> anyhow::Result<reqwest::Client> {
         HeaderValue::from_static("https://finance.yahoo.com/"),
     );
 
-    let client = match reqwest::Client::builder()
-        .default_headers(headers.clone())
-        .build()
-    {
-        Ok(cli) => cli,
-        Err(e) => return Err(e.into()),
-    };
-
-    Ok(client)
+    // Keep cookies so a consent page answered once stays answered for later articles
+    http::Client::new(
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .cookie_store(true),
+    )
 }
@@ -0,0 +1,275 @@
+use crossterm::style::Stylize;
+use std::fmt::Write;
+
+/// Relative difference allowed between a quoted figure and the statement value,
+/// covering rounding such as "9.0B" for 9.01B.
+const VALUE_TOLERANCE: f64 = 0.02;
+/// Absolute difference in percentage points allowed for quoted percentages.
+const PERCENT_TOLERANCE: f64 = 0.5;
+
+/// A number quoted in model output, normalised to a plain value.
+#[derive(Debug, Clone)]
+pub struct Figure {
+    /// Byte range of the figure in the text it was found in.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub value: f64,
+    pub percent: bool,
+    /// Written with a unit such as "B", "million" or "crore".
+    pub scaled: bool,
+}
+
+/// Multiplier for a unit written after a number, e.g. "1.2B" or "450 crore".
+fn unit_multiplier(unit: &str) -> Option<f64> {
+    match unit.to_lowercase().as_str() {
+        "k" | "thousand" => Some(1e3),
+        "lakh" | "lakhs" | "lac" | "lacs" => Some(1e5),
+        "m" | "mn" | "million" | "millions" => Some(1e6),
+        "cr" | "crore" | "crores" => Some(1e7),
+        "b" | "bn" | "billion" | "billions" => Some(1e9),
+        "t" | "tn" | "trillion" | "trillions" => Some(1e12),
+        _ => None,
+    }
+}
+
+/// Parse a statement cell as displayed, e.g. "9.01M", "-1.20B" or "12.34".
+fn parse_cell(cell: &str) -> Option<f64> {
+    let cell = cell.trim().replace(',', "");
+    if cell.is_empty() || cell == "-" || cell.contains('/') {
+        return None;
+    }
+    if let Ok(v) = cell.parse::<f64>() {
+        return Some(v);
+    }
+    let (last, _) = cell.char_indices().last()?;
+    let (number, unit) = cell.split_at(last);
+    Some(number.parse::<f64>().ok()? * unit_multiplier(unit)?)
+}
+
+/// Find every number in `text` with its unit. Dates, fiscal labels such as "FY2024"
+/// or "Q3", bare years and small counts are left out.
+pub fn extract_figures(text: &str) -> Vec<Figure> {
+    let bytes = text.as_bytes();
+    let mut figures = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut before = text[..start].chars().rev();
+        let (prev, prev2) = (before.next(), before.next());
+        while i < bytes.len()
+            && (bytes[i].is_ascii_digit()
+                || (matches!(bytes[i], b',' | b'.')
+                    && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit())))
+        {
+            i += 1;
+        }
+        let number_end = i;
+
+        // "-" is a sign unless it joins a label, as in "Q3-2024"
+        let joined = prev == Some('-') && prev2.is_some_and(|c| c.is_alphanumeric());
+        let labelled = prev.is_some_and(|c| c.is_alphabetic() || c == '/') || joined;
+        if labelled || bytes.get(number_end) == Some(&b'/') {
+            continue;
+        }
+        let negative = prev == Some('-');
+        let start = if negative { start - 1 } else { start };
+
+        let number = text[start..number_end].trim_start_matches('-');
+        let Ok(mut value) = number.replace(',', "").parse::<f64>() else {
+            continue;
+        };
+        if negative {
+            value = -value;
+        }
+
+        let mut end = number_end;
+        let mut percent = false;
+        let mut scaled = false;
+        let rest = &text[number_end..];
+        let word: String = rest
+            .trim_start_matches(' ')
+            .chars()
+            .take_while(|c| c.is_alphabetic())
+            .collect();
+        let spaced = rest.starts_with(' ');
+
+        if rest.trim_start_matches(' ').starts_with('%') {
+            percent = true;
+            end = number_end + rest.find('%').unwrap_or(0) + 1;
+        } else if word.eq_ignore_ascii_case("percent") {
+            percent = true;
+            end = number_end + rest.find(&word).unwrap_or(0) + word.len();
+        } else if let Some(m) = unit_multiplier(&word).filter(|_| !spaced || word.len() > 1) {
+            value *= m;
+            scaled = true;
+            end = number_end + rest.find(&word).unwrap_or(0) + word.len();
+        }
+
+        let plain = !percent && !scaled && !number.contains('.');
+        if plain && (value.abs() <= 12.0 || (1900.0..=2100.0).contains(&value)) {
+            continue;
+        }
+
+        figures.push(Figure {
+            start,
+            end,
+            text: text[start..end].to_string(),
+            value,
+            percent,
+            scaled,
+        });
+    }
+    figures
+}
+
+/// Row that margins are taken against.
+const REVENUE_ROW: &str = "Total Revenue";
+
+/// Values a review may quote, derived from the statement tables it was given.
+pub struct Evidence {
+    values: Vec<f64>,
+    /// Changes of one row between two periods, in percent.
+    growth: Vec<f64>,
+    /// Rows of a table as a share of its Total Revenue in the same period, in percent.
+    margins: Vec<f64>,
+}
+
+impl Evidence {
+    /// Read the values out of the rendered statement tables (`Metric | v1 | v2 |`),
+    /// along with each row's change between periods and its margin on revenue.
+    pub fn from_statements(statements: &str) -> Self {
+        let mut values = Vec::new();
+        let mut growth = Vec::new();
+        let mut margins = Vec::new();
+        let mut tables: Vec<Vec<(String, Vec<Option<f64>>)>> = Vec::new();
+
+        for line in statements.lines() {
+            let cells: Vec<&str> = line.split('|').collect();
+            if cells.len() < 3 {
+                continue;
+            }
+            let label = cells[0].trim();
+            if label == "Metric" || tables.is_empty() {
+                tables.push(Vec::new());
+                if label == "Metric" {
+                    continue;
+                }
+            }
+            let row: Vec<Option<f64>> = cells[1..].iter().map(|c| parse_cell(c)).collect();
+            values.extend(row.iter().flatten().map(|v| v.abs()));
+            let periods: Vec<f64> = row.iter().flatten().copied().collect();
+            for (i, new) in periods.iter().enumerate() {
+                for old in &periods[i + 1..] {
+                    if *old != 0.0 {
+                        growth.push(((new - old) / old.abs() * 100.0).abs());
+                    }
+                }
+            }
+            if let Some(table) = tables.last_mut() {
+                table.push((label.to_string(), row));
+            }
+        }
+
+        for table in &tables {
+            let Some((_, revenue)) = table
+                .iter()
+                .find(|(label, _)| label.eq_ignore_ascii_case(REVENUE_ROW))
+            else {
+                continue;
+            };
+            for (label, row) in table {
+                if label.eq_ignore_ascii_case(REVENUE_ROW) {
+                    continue;
+                }
+                for (v, total) in row.iter().zip(revenue) {
+                    if let (Some(v), Some(total)) = (v, total)
+                        && *total != 0.0
+                    {
+                        margins.push((v / total * 100.0).abs());
+                    }
+                }
+            }
+        }
+
+        Self {
+            values,
+            growth,
+            margins,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= VALUE_TOLERANCE * b.abs().max(1e-9)
+    }
+
+    /// Whether `figure` matches a statement value, allowing for the values being
+    /// reported in thousands and for numbers quoted without their unit.
+    pub fn supports(&self, figure: &Figure) -> bool {
+        let quoted = figure.value.abs();
+        if figure.percent {
+            let near = |p: f64| (quoted - p).abs() <= PERCENT_TOLERANCE || Self::close(quoted, p);
+            return self.growth.iter().chain(&self.margins).any(|p| near(*p));
+        }
+        if figure.scaled {
+            return self
+                .values
+                .iter()
+                .any(|v| Self::close(quoted, *v) || Self::close(quoted, v * 1e3));
+        }
+        self.values.iter().any(|v| {
+            [1.0, 1e3, 1e6, 1e9, 1e12]
+                .iter()
+                .any(|scale| Self::close(quoted, v / scale))
+        })
+    }
+}
+
+/// Figures in `review` that match nothing in `statements`.
+pub fn unsupported(review: &str, statements: &str) -> Vec<Figure> {
+    let evidence = Evidence::from_statements(statements);
+    if evidence.is_empty() {
+        return Vec::new();
+    }
+    extract_figures(review)
+        .into_iter()
+        .filter(|f| !evidence.supports(f))
+        .collect()
+}
+
+/// Highlight each unsupported figure with a numbered marker and list them as
+/// footnotes after the text.
+pub fn annotate(review: &str, unsupported: &[Figure]) -> String {
+    if unsupported.is_empty() {
+        return review.to_string();
+    }
+
+    let mut out = String::new();
+    let mut last = 0;
+    for (idx, figure) in unsupported.iter().enumerate() {
+        out.push_str(&review[last..figure.start]);
+        let marked = format!("{}[?{}]", figure.text, idx + 1);
+        write!(&mut out, "{}", marked.yellow()).ok();
+        last = figure.end;
+    }
+    out.push_str(&review[last..]);
+
+    writeln!(
+        &mut out,
+        "\n\nUnverified figures (not found in the statement data):"
+    )
+    .ok();
+    for (idx, figure) in unsupported.iter().enumerate() {
+        writeln!(&mut out, "  [?{}] {}", idx + 1, figure.text).ok();
+    }
+    out
+}